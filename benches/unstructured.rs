@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rustyknife::behaviour::Intl;
+use rustyknife::rfc5322::unstructured;
+
+fn bench_unstructured(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unstructured");
+
+    // Plain ASCII takes the fast path.
+    group.bench_function("ascii", |b| {
+        b.iter(|| unstructured::<Intl>(black_box(b" Re: meeting about the quarterly report")))
+    });
+    // Folding forces the full parser on otherwise identical text.
+    group.bench_function("folded", |b| {
+        b.iter(|| unstructured::<Intl>(black_box(b" Re: meeting about the\r\n quarterly report")))
+    });
+    group.bench_function("encoded", |b| {
+        b.iter(|| {
+            unstructured::<Intl>(black_box(b" Re: =?utf-8?q?r=C3=A9union?= about the report"))
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_unstructured);
+criterion_main!(benches);
@@ -6,11 +6,17 @@
 //!
 //! [RFC 5322]: https://tools.ietf.org/html/rfc5322
 
+use crate::rfc2047::fold_encoded;
+use crate::rfc2231::encode_content_type;
+use crate::rfc2231::is_attribute_char;
+use crate::rfc2231::is_token_char;
+use crate::rfc5322::Address;
 use crate::util::*;
 use nom::branch::alt;
 use nom::bytes::streaming::tag;
 use nom::bytes::streaming::take_until;
 use nom::bytes::streaming::take_while1;
+use nom::combinator::consumed;
 use nom::combinator::map;
 use nom::combinator::map_opt;
 use nom::combinator::opt;
@@ -21,6 +27,7 @@ use nom::sequence::pair;
 use nom::sequence::separated_pair;
 use nom::sequence::terminated;
 use std::borrow::Cow;
+use std::fmt::Write;
 use std::str;
 
 fn fws(input: &[u8]) -> NomResult<'_, Cow<'_, str>> {
@@ -73,10 +80,22 @@ fn crlf(input: &[u8]) -> NomResult<'_, &[u8]> {
 /// - The [`Err`] variant is returned when the the first line of a header
 ///   does not contain a colon or contains 8bit bytes on the left hand
 ///   side of the colon.
+///
+/// NUL bytes are never valid in a header. A field containing one is
+/// returned as an [`Err`] covering all of its lines, so a NUL can't
+/// be smuggled through in a value.
 pub type HeaderField<'a> = Result<(&'a [u8], &'a [u8]), &'a [u8]>;
 
+fn is_ftext(c: u8) -> bool {
+    matches!(c, 33..=57 | 59..=126)
+}
+
 fn field_name(input: &[u8]) -> NomResult<'_, &[u8]> {
-    take_while1(|c| matches!(c, 33..=57 | 59..=126))(input)
+    take_while1(is_ftext)(input)
+}
+
+fn field_name_8bit(input: &[u8]) -> NomResult<'_, &[u8]> {
+    take_while1(|c| matches!(c, 33..=57 | 59..=126 | 128..=255))(input)
 }
 
 fn until_crlf(input: &[u8]) -> NomResult<'_, &[u8]> {
@@ -96,10 +115,34 @@ fn unstructured(input: &[u8]) -> NomResult<'_, &[u8]> {
     ))(input)
 }
 
+// A NUL byte anywhere in a field makes the whole field invalid,
+// including its continuation lines, so that it cannot be passed on
+// as a valid value.
+fn _reject_nul<'a>(raw: &'a [u8], (name, value): (&'a [u8], &'a [u8])) -> HeaderField<'a> {
+    if value.contains(&0) {
+        Err(raw.strip_suffix(b"\r\n").unwrap_or(raw))
+    } else {
+        Ok((name, value))
+    }
+}
+
 fn field(input: &[u8]) -> NomResult<'_, HeaderField<'_>> {
     map(
-        terminated(separated_pair(field_name, tag(":"), unstructured), crlf),
-        Ok,
+        consumed(terminated(
+            separated_pair(field_name, tag(":"), unstructured),
+            crlf,
+        )),
+        |(raw, field)| _reject_nul(raw, field),
+    )(input)
+}
+
+fn field_8bit(input: &[u8]) -> NomResult<'_, HeaderField<'_>> {
+    map(
+        consumed(terminated(
+            separated_pair(field_name_8bit, tag(":"), unstructured),
+            crlf,
+        )),
+        |(raw, field)| _reject_nul(raw, field),
     )(input)
 }
 
@@ -120,3 +163,277 @@ pub fn header_section(input: &[u8]) -> NomResult<'_, Vec<HeaderField<'_>>> {
 pub fn header(input: &[u8]) -> NomResult<'_, Option<HeaderField<'_>>> {
     alt((map(alt((field, invalid_field)), Some), map(crlf, |_| None)))(input)
 }
+
+/// Like [`header_section`], but accepts 8bit bytes in header names.
+///
+/// Malformed messages sometimes contain header names with bytes
+/// above 127. [`header_section`] returns these lines as an [`Err`]
+/// [`HeaderField`], which separates the name from its value. This
+/// variant returns them as valid fields instead, for maximal recovery
+/// when walking untrusted mail.
+///
+/// # Examples
+/// ```
+/// use rustyknife::headersection::header_section_lenient;
+///
+/// let (_, fields) = header_section_lenient(b"X-F\xf6o: bar\r\n\r\n").unwrap();
+/// assert_eq!(fields, [Ok((&b"X-F\xf6o"[..], &b" bar"[..]))]);
+/// ```
+pub fn header_section_lenient(input: &[u8]) -> NomResult<'_, Vec<HeaderField<'_>>> {
+    terminated(many0(alt((field_8bit, invalid_field))), opt(crlf))(input)
+}
+
+/// Like [`header`], but accepts 8bit bytes in header names.
+///
+/// See [`header_section_lenient`].
+pub fn header_lenient(input: &[u8]) -> NomResult<'_, Option<HeaderField<'_>>> {
+    alt((
+        map(alt((field_8bit, invalid_field)), Some),
+        map(crlf, |_| None),
+    ))(input)
+}
+
+/// Find the value of the first valid header named `name`.
+///
+/// The name is compared case-insensitively and the value is returned
+/// as it appears in the message, including folding.
+///
+/// # Examples
+/// ```
+/// use rustyknife::headersection::{find_header, header_section};
+///
+/// let (_, fields) = header_section(b"From: a@example.org\r\nSubject: Hi\r\n\r\n").unwrap();
+/// assert_eq!(find_header(&fields, "subject"), Some(&b" Hi"[..]));
+/// assert_eq!(find_header(&fields, "To"), None);
+/// ```
+pub fn find_header<'a>(fields: &[HeaderField<'a>], name: &str) -> Option<&'a [u8]> {
+    fields.iter().find_map(|field| match field {
+        Ok((n, value)) if n.eq_ignore_ascii_case(name.as_bytes()) => Some(*value),
+        _ => None,
+    })
+}
+
+/// Remove folding from a header value.
+///
+/// Every CRLF that is followed by whitespace is removed, as described
+/// in [RFC 5322 section 2.2.3]. The input is borrowed when it contains
+/// no folding. Other whitespace is left untouched, see
+/// [`canonicalize_relaxed`] for the DKIM transformation.
+///
+/// [RFC 5322 section 2.2.3]: https://tools.ietf.org/html/rfc5322#section-2.2.3
+/// [`canonicalize_relaxed`]: crate::rfc6376::canonicalize_relaxed
+pub fn unfold(value: &[u8]) -> Cow<'_, [u8]> {
+    let is_fold = |w: &[u8]| w[0] == b'\r' && w[1] == b'\n' && matches!(w[2], b' ' | b'\t');
+
+    if !value.windows(3).any(is_fold) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut out = Vec::with_capacity(value.len());
+    let mut i = 0;
+    while i < value.len() {
+        if value.len() - i >= 3 && is_fold(&value[i..i + 3]) {
+            i += 2;
+        } else {
+            out.push(value[i]);
+            i += 1;
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Zero copy view of a valid [`HeaderField`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::headersection::{header_section, HeaderFieldRef};
+///
+/// let (_, fields) = header_section(b"Subject: Hello\r\n world\r\n\r\n").unwrap();
+/// let field = HeaderFieldRef::from(fields[0].unwrap());
+/// assert!(field.name().eq_ignore_ascii_case(b"subject"));
+/// assert_eq!(field.raw_value(), b" Hello\r\n world");
+/// assert_eq!(&*field.value(), b" Hello world");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeaderFieldRef<'a> {
+    name: &'a [u8],
+    value: &'a [u8],
+}
+
+impl<'a> HeaderFieldRef<'a> {
+    /// The header name, without the colon.
+    pub fn name(&self) -> &'a [u8] {
+        self.name
+    }
+
+    /// The header value as it appears in the message, including
+    /// folding.
+    pub fn raw_value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// The unfolded header value.
+    ///
+    /// Only allocates if the value is folded. See [`unfold`].
+    pub fn value(&self) -> Cow<'a, [u8]> {
+        unfold(self.value)
+    }
+}
+
+impl<'a> From<(&'a [u8], &'a [u8])> for HeaderFieldRef<'a> {
+    fn from((name, value): (&'a [u8], &'a [u8])) -> Self {
+        HeaderFieldRef { name, value }
+    }
+}
+
+/// Check whether a header section contains raw non-ASCII bytes.
+///
+/// Such a message relies on [RFC 6532] and cannot be relayed to a
+/// server that does not support SMTPUTF8. RFC 2047 encoded words are
+/// plain ASCII and so do not count. Invalid header lines are checked
+/// too.
+///
+/// # Examples
+/// ```
+/// use rustyknife::headersection::{header_section, message_requires_utf8};
+///
+/// let (_, fields) = header_section(b"Subject: =?utf-8?q?caf=C3=A9?=\r\n\r\n").unwrap();
+/// assert!(!message_requires_utf8(&fields));
+/// let (_, fields) = header_section("Subject: café\r\n\r\n".as_bytes()).unwrap();
+/// assert!(message_requires_utf8(&fields));
+/// ```
+///
+/// [RFC 6532]: https://tools.ietf.org/html/rfc6532
+pub fn message_requires_utf8(fields: &[HeaderField<'_>]) -> bool {
+    fields.iter().any(|field| match field {
+        Ok((name, value)) => !name.is_ascii() || !value.is_ascii(),
+        Err(line) => !line.is_ascii(),
+    })
+}
+
+/// A header value to be serialized by [`emit_header`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeaderValue {
+    /// Free text such as a `"Subject:"`. RFC 2047 encoded if needed.
+    Unstructured(String),
+    /// A list of addresses such as a `"To:"`.
+    Addresses(Vec<Address>),
+    /// A MIME `"Content-Type:"`.
+    ContentType {
+        /// The MIME type, for example `text/plain`.
+        mime_type: String,
+        /// The parameters. Values are quoted or RFC 2231 encoded as
+        /// needed.
+        params: Vec<(String, String)>,
+    },
+}
+
+// Split a value on the spaces where it may be folded. Spaces inside
+// quoted strings are left alone.
+fn fold_points(value: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ' ' if !quoted => {
+                out.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    out.push(&value[start..]);
+
+    out
+}
+
+// Write "name: value" followed by CRLF, folding at the spaces between
+// words so that lines stay within `max_line` columns where possible.
+// Runs of spaces produce empty words, which are never folded before
+// so that no continuation line is only whitespace.
+pub(crate) fn fold_line(name: &str, value: &str, max_line: usize) -> String {
+    let mut out = format!("{}:", name);
+    let mut line_len = out.len();
+
+    for word in fold_points(value) {
+        if !word.is_empty() && line_len > 0 && line_len + 1 + word.len() > max_line {
+            out.push_str("\r\n");
+            line_len = 0;
+        }
+        out.push(' ');
+        out.push_str(word);
+        line_len += 1 + word.len();
+    }
+    out.push_str("\r\n");
+
+    out
+}
+
+/// Serialize a complete header line, including the trailing CRLF.
+///
+/// The value is formatted according to its kind, RFC 2047 or RFC 2231
+/// encoded where needed and folded to 78 columns where possible. The
+/// output parses back to the same value with [`header_section`] and
+/// the parser for that header.
+///
+/// Fails if `name` is not a valid field name, if a MIME type is not a
+/// `type/subtype` pair of tokens or if a parameter name is not a token
+/// without `*`, `'` and `%`. These are written as is and could
+/// otherwise be used to inject other headers.
+///
+/// # Examples
+/// ```
+/// use rustyknife::headersection::{emit_header, HeaderValue};
+///
+/// let value = HeaderValue::ContentType {
+///     mime_type: "text/plain".into(),
+///     params: vec![("charset".into(), "utf-8".into())],
+/// };
+/// assert_eq!(emit_header("Content-Type", &value).unwrap(), "Content-Type: text/plain; charset=utf-8\r\n");
+///
+/// let value = HeaderValue::Unstructured("hi".into());
+/// assert!(emit_header("X-A\r\nBcc", &value).is_err());
+/// ```
+pub fn emit_header(name: &str, value: &HeaderValue) -> Result<String, &'static str> {
+    const MAX_LINE: usize = 78;
+
+    if name.is_empty() || !name.bytes().all(is_ftext) {
+        return Err("invalid header name");
+    }
+
+    let value = match value {
+        HeaderValue::Unstructured(text) => return Ok(fold_encoded(name, text, MAX_LINE)),
+        HeaderValue::Addresses(addresses) => {
+            let mut out = String::new();
+            for (i, address) in addresses.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write!(out, "{}", address).unwrap();
+            }
+            out
+        }
+        HeaderValue::ContentType { mime_type, params } => {
+            let is_token = |t: &str| !t.is_empty() && t.bytes().all(is_token_char);
+            match mime_type.split_once('/') {
+                Some((t, s)) if is_token(t) && is_token(s) => (),
+                _ => return Err("invalid MIME type"),
+            }
+            if !params
+                .iter()
+                .all(|(n, _)| !n.is_empty() && n.bytes().all(is_attribute_char))
+            {
+                return Err("invalid parameter name");
+            }
+            encode_content_type(mime_type, params)
+        }
+    };
+
+    Ok(fold_line(name, &value, MAX_LINE))
+}
@@ -3,6 +3,7 @@
 // #![warn(missing_docs)]
 #![allow(clippy::result_unit_err)]
 
+pub use encoding_rs;
 pub use nom;
 
 /// Types used for varying parser behaviour.
@@ -15,17 +16,55 @@ pub mod behaviour {
     ///  * Activates message/global (RFC6532) support for message content.
     ///  * Activates SMTPUTF8 support for SMTP.
     pub struct Intl;
+
+    /// Comments between the words of a display name are dropped
+    /// instead of being treated as whitespace.
+    ///
+    /// `"John" (the) "Smith"` gives `"JohnSmith"` rather than
+    /// `"John Smith"`. Wraps another policy such as [`Intl`].
+    pub struct ElideComments<P>(std::marker::PhantomData<P>);
+
+    /// Common deviations from the standards are accepted.
+    ///
+    /// Wraps another policy such as [`Intl`]. The parsers document what
+    /// they accept in this mode.
+    pub struct Lenient<P>(std::marker::PhantomData<P>);
+
+    /// Groups are rejected in `"From:"` and `"Sender:"` as in RFC 5322.
+    ///
+    /// By default they are accepted as allowed by RFC 6854 for
+    /// automated senders. Wraps another policy such as [`Intl`].
+    pub struct NoGroupSenders<P>(std::marker::PhantomData<P>);
+
+    /// Comments may be nested up to `N` deep instead of the default of
+    /// 100.
+    ///
+    /// Deeper comments are a parse error. Wraps another policy such as
+    /// [`Intl`].
+    pub struct CommentDepth<P, const N: usize>(std::marker::PhantomData<P>);
 }
 
 #[macro_use]
 mod util;
 pub mod headersection;
+pub mod nonstandard;
+#[cfg(feature = "std-io")]
+pub mod reader;
+pub mod rfc1864;
+pub mod rfc2045;
 pub mod rfc2047;
 pub mod rfc2231;
+pub mod rfc2369;
 pub mod rfc3461;
+pub mod rfc3463;
+pub mod rfc3676;
 mod rfc5234;
 pub mod rfc5321;
 pub mod rfc5322;
+pub mod rfc6376;
+pub mod rfc6522;
+#[cfg(feature = "proptest")]
+pub mod strategy;
 pub mod types;
 pub mod xforward;
 
@@ -0,0 +1,144 @@
+//! Parsers for common headers that are not defined by any RFC
+
+use crate::rfc5234::crlf;
+use crate::rfc5322::addr_spec;
+use crate::rfc5322::ofws;
+use crate::rfc5322::unstructured;
+use crate::rfc5322::Utf8Policy;
+use crate::types::Mailbox;
+use crate::util::*;
+use nom::bytes::complete::take_while1;
+use nom::combinator::map;
+use nom::combinator::map_opt;
+use nom::combinator::opt;
+use nom::multi::fold_many1;
+use nom::sequence::delimited;
+use nom::sequence::terminated;
+
+/// Parse the content of an `"X-Loop:"` header.
+///
+/// The header is unstructured. It usually contains an address
+/// identifying the software that handled the message. Surrounding
+/// whitespace is removed so the value can be compared directly.
+pub fn x_loop<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, String> {
+    map(unstructured::<P>, |s| s.trim().into())(i)
+}
+
+/// Parse the content of an Outlook `"Thread-Topic:"` header.
+///
+/// The header is unstructured and usually holds the original subject
+/// without any `Re:` prefixes. Surrounding whitespace is removed.
+pub fn thread_topic<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, String> {
+    map(unstructured::<P>, |s| s.trim().into())(i)
+}
+
+/// Parse the content of an Outlook `"Thread-Index:"` header.
+///
+/// The header is base64 of a binary structure. The decoded bytes are
+/// returned as-is. Folding whitespace inside the value is allowed and
+/// padding is optional.
+///
+/// # Examples
+/// ```
+/// use rustyknife::nonstandard::thread_index;
+///
+/// let (_, index) = thread_index(b" AQHT\r\n dA==").unwrap();
+/// assert_eq!(index, [0x01, 0x01, 0xd3, 0x74]);
+/// ```
+pub fn thread_index(i: &[u8]) -> NomResult<'_, Vec<u8>> {
+    let is_base64 = |c: u8| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'=');
+
+    map_opt(
+        fold_many1(
+            delimited(ofws, take_while1(is_base64), ofws),
+            Vec::new(),
+            |mut acc, chunk: &[u8]| {
+                acc.extend_from_slice(chunk);
+                acc
+            },
+        ),
+        |encoded| decode_base64_tolerant(&encoded),
+    )(i)
+}
+
+/// Parse the content of a `"Delivered-To:"` header.
+///
+/// The header is added by delivery agents such as Postfix and
+/// contains a bare address, which makes it useful for loop detection.
+/// Surrounding CFWS is allowed.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::nonstandard::delivered_to;
+///
+/// let (_, mailbox) = delivered_to::<Intl>(b" user@example.org").unwrap();
+/// assert_eq!(mailbox.to_string(), "user@example.org");
+/// ```
+pub fn delivered_to<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Mailbox> {
+    _bare_address::<P>(i)
+}
+
+// A header holding a bare address with optional CFWS and CRLF.
+fn _bare_address<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Mailbox> {
+    terminated(addr_spec::<P>, opt(crlf))(i)
+}
+
+/// Parse the content of a Postfix `"X-Original-To:"` header.
+///
+/// The header contains the bare envelope recipient address before
+/// any alias expansion, which makes it useful for recovering the
+/// address a message was originally sent to. Surrounding CFWS is
+/// allowed.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::nonstandard::x_original_to;
+///
+/// let (_, mailbox) = x_original_to::<Intl>(b" postmaster@example.org").unwrap();
+/// assert_eq!(mailbox.to_string(), "postmaster@example.org");
+/// ```
+pub fn x_original_to<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Mailbox> {
+    _bare_address::<P>(i)
+}
+
+/// Extract the list address from a legacy `"Mailing-List:"` header.
+///
+/// The header is a `";"` separated list of entries such as
+/// `list group@example.org` and `contact group-owner@example.org`.
+/// Returns the address of the `list` entry, or failing that the first
+/// address found in any entry.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::nonstandard::mailing_list;
+///
+/// let list = mailing_list::<Intl>(b" contact help@example.org; run by ezmlm").unwrap();
+/// assert_eq!(list.to_string(), "help@example.org");
+/// ```
+pub fn mailing_list<P: Utf8Policy>(i: &[u8]) -> Option<Mailbox> {
+    let mut first = None;
+
+    for entry in i.split(|&c| c == b';') {
+        let entry = entry.trim_ascii();
+        let split = entry
+            .iter()
+            .position(|c| c.is_ascii_whitespace())
+            .unwrap_or(entry.len());
+        let (keyword, rest) = entry.split_at(split);
+
+        if let Ok((rem, mailbox)) = addr_spec::<P>(rest.trim_ascii()) {
+            if !rem.is_empty() {
+                continue;
+            }
+            if keyword.eq_ignore_ascii_case(b"list") {
+                return Some(mailbox);
+            }
+            first.get_or_insert(mailbox);
+        }
+    }
+
+    first
+}
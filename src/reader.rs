@@ -0,0 +1,227 @@
+//! Streaming message reader
+//!
+//! Reads a message from an [`io::Read`] without buffering it whole.
+//! Requires the `std-io` feature.
+
+use crate::headersection::find_header;
+use crate::headersection::header;
+use crate::headersection::header_section;
+use crate::headersection::HeaderField;
+use crate::rfc2045::Base64Decoder;
+use crate::rfc2045::QpDecoder;
+use crate::rfc2231::content_transfer_encoding;
+use crate::rfc2231::ContentTransferEncoding;
+use std::io::{self, Read};
+use std::mem;
+
+const CHUNK_SIZE: usize = 8192;
+
+/// The default limit on the size of the header section.
+pub const DEFAULT_MAX_HEADER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug)]
+enum BodyDecoder {
+    Identity,
+    QuotedPrintable(QpDecoder),
+    Base64(Base64Decoder),
+}
+
+impl BodyDecoder {
+    fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        match self {
+            BodyDecoder::Identity => input.to_vec(),
+            BodyDecoder::QuotedPrintable(d) => d.update(input),
+            BodyDecoder::Base64(d) => d.update(input),
+        }
+    }
+
+    fn finalize(&mut self) -> Vec<u8> {
+        match mem::replace(self, BodyDecoder::Identity) {
+            BodyDecoder::Identity => Vec::new(),
+            BodyDecoder::QuotedPrintable(d) => d.finalize(),
+            BodyDecoder::Base64(d) => d.finalize(),
+        }
+    }
+}
+
+/// Reads the header section of a message, then its transfer decoded
+/// body.
+///
+/// The header section is read when the reader is created. The body is
+/// then read through the [`Read`] implementation with its
+/// `"Content-Transfer-Encoding"` undone. Quoted-printable and base64
+/// are decoded, anything else is passed through unchanged.
+///
+/// # Memory use
+/// The header section is held in memory and is limited to
+/// [`DEFAULT_MAX_HEADER_SIZE`] bytes by default. The body is read in
+/// chunks of 8 KiB, so only one chunk and its decoded form are held at
+/// a time. The quoted-printable decoder additionally holds back
+/// trailing whitespace until the end of its line is seen.
+///
+/// # Examples
+/// ```
+/// use rustyknife::reader::MessageReader;
+/// use std::io::Read;
+///
+/// let message = &b"Content-Transfer-Encoding: base64\r\n\r\naGVsbG8gd29ybGQ=\r\n"[..];
+/// let mut reader = MessageReader::new(message).unwrap();
+/// assert_eq!(reader.headers().len(), 1);
+///
+/// let mut body = String::new();
+/// reader.read_to_string(&mut body).unwrap();
+/// assert_eq!(body, "hello world");
+/// ```
+#[derive(Debug)]
+pub struct MessageReader<R> {
+    inner: R,
+    header: Vec<u8>,
+    encoding: Option<ContentTransferEncoding>,
+    decoder: BodyDecoder,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    output_pos: usize,
+    done: bool,
+}
+
+impl<R: Read> MessageReader<R> {
+    /// Read the header section from `inner`.
+    ///
+    /// See [`MessageReader::with_max_header_size`].
+    pub fn new(inner: R) -> io::Result<Self> {
+        Self::with_max_header_size(inner, DEFAULT_MAX_HEADER_SIZE)
+    }
+
+    /// Read the header section from `inner`, failing if it is larger
+    /// than `max` bytes including the blank line that ends it.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the header section
+    /// is too large and [`io::ErrorKind::UnexpectedEof`] if the input
+    /// ends in the middle of a header. A message without a body does
+    /// not need the blank line that ends the header section.
+    pub fn with_max_header_size(mut inner: R, max: usize) -> io::Result<Self> {
+        let mut buf = Vec::new();
+        let mut pos = 0;
+        let mut eof = false;
+        let too_large = || io::Error::new(io::ErrorKind::InvalidData, "header section too large");
+
+        loop {
+            match header(&buf[pos..]) {
+                Ok((rem, Some(_))) => pos = buf.len() - rem.len(),
+                Ok((rem, None)) => {
+                    pos = buf.len() - rem.len();
+                    break;
+                }
+                Err(nom::Err::Incomplete(_)) if !eof => {
+                    if buf.len() > max {
+                        return Err(too_large());
+                    }
+
+                    // Read at most one byte past the limit.
+                    let mut chunk = [0; CHUNK_SIZE];
+                    let len = CHUNK_SIZE.min((max - buf.len()).saturating_add(1));
+                    let n = inner.read(&mut chunk[..len])?;
+                    buf.extend_from_slice(&chunk[..n]);
+
+                    if n == 0 {
+                        // Terminate the header section of a message
+                        // without a body.
+                        buf.extend_from_slice(b"\r\n");
+                        eof = true;
+                    }
+                }
+                Err(nom::Err::Incomplete(_)) => {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                Err(_) => return Err(io::ErrorKind::InvalidData.into()),
+            }
+        }
+
+        // The line break added at the end of the input doesn't count
+        // towards the limit.
+        let end = if eof { buf.len() - 2 } else { buf.len() };
+        if pos.min(end) > max {
+            return Err(too_large());
+        }
+
+        let input = buf.split_off(pos);
+        let mut reader = MessageReader {
+            inner,
+            header: buf,
+            encoding: None,
+            decoder: BodyDecoder::Identity,
+            input,
+            output: Vec::new(),
+            output_pos: 0,
+            done: eof,
+        };
+
+        reader.encoding = find_header(&reader.headers(), "Content-Transfer-Encoding")
+            .and_then(|v| content_transfer_encoding(v).ok())
+            .map(|(_, cte)| cte);
+        reader.decoder = match reader.encoding {
+            Some(ContentTransferEncoding::QuotedPrintable) => {
+                BodyDecoder::QuotedPrintable(QpDecoder::new())
+            }
+            Some(ContentTransferEncoding::Base64) => BodyDecoder::Base64(Base64Decoder::new()),
+            _ => BodyDecoder::Identity,
+        };
+
+        Ok(reader)
+    }
+}
+
+impl<R> MessageReader<R> {
+    /// The raw header section, including the blank line that ends it.
+    pub fn header_bytes(&self) -> &[u8] {
+        &self.header
+    }
+
+    /// The header fields, as returned by [`header_section`].
+    pub fn headers(&self) -> Vec<HeaderField<'_>> {
+        header_section(&self.header)
+            .map(|(_, fields)| fields)
+            .unwrap_or_default()
+    }
+
+    /// The parsed `"Content-Transfer-Encoding"`, if present and valid.
+    pub fn content_transfer_encoding(&self) -> Option<&ContentTransferEncoding> {
+        self.encoding.as_ref()
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for MessageReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let available = &self.output[self.output_pos..];
+            if !available.is_empty() {
+                let n = available.len().min(buf.len());
+                buf[..n].copy_from_slice(&available[..n]);
+                self.output_pos += n;
+                return Ok(n);
+            }
+
+            self.output.clear();
+            self.output_pos = 0;
+            if !self.input.is_empty() {
+                self.output = self.decoder.update(&self.input);
+                self.input.clear();
+            } else if self.done {
+                self.output = self.decoder.finalize();
+                if self.output.is_empty() {
+                    return Ok(0);
+                }
+            } else {
+                let mut chunk = [0; CHUNK_SIZE];
+                let n = self.inner.read(&mut chunk)?;
+                self.input.extend_from_slice(&chunk[..n]);
+                self.done = n == 0;
+            }
+        }
+    }
+}
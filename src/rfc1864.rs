@@ -0,0 +1,30 @@
+//! [Content-MD5] header
+//!
+//! [Content-MD5]: https://tools.ietf.org/html/rfc1864
+
+use crate::util::*;
+use nom::bytes::complete::take_while1;
+use nom::combinator::map_opt;
+use std::convert::TryInto;
+
+/// Parse the content of a `"Content-MD5:"` header.
+///
+/// The header is the base64 encoded MD5 digest of the body. Folding
+/// whitespace inside the value and missing padding are tolerated.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc1864::content_md5;
+///
+/// let (_, digest) = content_md5(b" Q2hlY2sgSW50ZWdyaXR5IQ==").unwrap();
+/// assert_eq!(&digest, b"Check Integrity!");
+/// ```
+pub fn content_md5(i: &[u8]) -> NomResult<'_, [u8; 16]> {
+    let is_base64 = |c: u8| {
+        c.is_ascii_alphanumeric() || c.is_ascii_whitespace() || matches!(c, b'+' | b'/' | b'=')
+    };
+
+    map_opt(take_while1(is_base64), |encoded| {
+        decode_base64_tolerant(encoded)?.try_into().ok()
+    })(i)
+}
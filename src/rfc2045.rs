@@ -0,0 +1,280 @@
+//! [MIME] body decoding
+//!
+//! Decoding is lenient: malformed input is decoded as well as possible
+//! instead of being rejected.
+//!
+//! [MIME]: https://tools.ietf.org/html/rfc2045
+
+use crate::headersection::HeaderField;
+use crate::rfc2231::content_transfer_encoding;
+use crate::rfc2231::content_type;
+use crate::rfc2231::ContentTransferEncoding;
+use base64::engine::general_purpose::GeneralPurpose;
+use base64::engine::general_purpose::GeneralPurposeConfig;
+use base64::engine::DecodePaddingMode;
+use base64::Engine as _;
+use encoding_rs::Encoding;
+use encoding_rs::WINDOWS_1252;
+
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+// Decode the "=XX" escapes of a single line. Invalid escapes are kept
+// as is.
+fn decode_qp_line(line: &[u8], out: &mut Vec<u8>) {
+    let mut i = 0;
+
+    while i < line.len() {
+        if line[i] == b'=' && i + 2 < line.len() {
+            if let (Some(hi), Some(lo)) = (hex_value(line[i + 1]), hex_value(line[i + 2])) {
+                out.push(hi << 4 | lo);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(line[i]);
+        i += 1;
+    }
+}
+
+/// Decode a quoted-printable body.
+///
+/// Soft line breaks are removed along with trailing whitespace on each
+/// line. Hard line breaks are kept as they appear in the input.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2045::decode_quoted_printable;
+///
+/// assert_eq!(decode_quoted_printable(b"caf=C3=A9 =\r\nau lait  \r\n"), "café au lait\r\n".as_bytes());
+/// ```
+pub fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut rem = input;
+
+    while !rem.is_empty() {
+        let (line, eol) = match rem.iter().position(|&c| c == b'\n') {
+            Some(lf) if lf > 0 && rem[lf - 1] == b'\r' => (&rem[..lf - 1], &rem[lf - 1..=lf]),
+            Some(lf) => (&rem[..lf], &rem[lf..=lf]),
+            None => (rem, &rem[rem.len()..]),
+        };
+        rem = &rem[line.len() + eol.len()..];
+
+        let mut line = line;
+        while let [rest @ .., b' ' | b'\t'] = line {
+            line = rest;
+        }
+
+        match line.strip_suffix(b"=") {
+            Some(line) => decode_qp_line(line, &mut out),
+            None => {
+                decode_qp_line(line, &mut out);
+                out.extend_from_slice(eol);
+            }
+        }
+    }
+
+    out
+}
+
+/// Incremental quoted-printable decoder.
+///
+/// Decodes input given in chunks as [`decode_quoted_printable`] would
+/// decode the whole input at once. Escapes and soft line breaks may be
+/// split across chunks: the bytes that cannot be decoded yet are held
+/// back until the next call. These are at most two bytes of an escape
+/// plus any whitespace that might turn out to be at the end of a line.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2045::QpDecoder;
+///
+/// let mut decoder = QpDecoder::new();
+/// let mut out = decoder.update(b"caf=C");
+/// out.extend(decoder.update(b"3=A9 =\r"));
+/// out.extend(decoder.update(b"\nau lait"));
+/// out.extend(decoder.finalize());
+/// assert_eq!(out, "café au lait".as_bytes());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct QpDecoder {
+    pending: Vec<u8>,
+}
+
+impl QpDecoder {
+    /// Create a new decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Length of the prefix of the pending input whose decoding does
+    // not depend on what follows.
+    fn decodable_len(&self) -> usize {
+        let mut end = self.pending.len();
+
+        loop {
+            match self.pending[..end] {
+                [.., b'=' | b'\r' | b' ' | b'\t'] => end -= 1,
+                [.., b'=', _] => end -= 2,
+                _ => return end,
+            }
+        }
+    }
+
+    /// Decode the next chunk of input.
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        self.pending.extend_from_slice(input);
+        let end = self.decodable_len();
+        let out = decode_quoted_printable(&self.pending[..end]);
+        self.pending.drain(..end);
+
+        out
+    }
+
+    /// Decode the input that was held back.
+    pub fn finalize(self) -> Vec<u8> {
+        decode_quoted_printable(&self.pending)
+    }
+}
+
+/// Decode a base64 body.
+///
+/// Line breaks and any other characters outside of the base64
+/// alphabet are ignored, as is missing or extraneous padding.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2045::decode_base64;
+///
+/// assert_eq!(decode_base64(b"aGVsbG8g\r\nd29ybGQ"), b"hello world");
+/// ```
+pub fn decode_base64(input: &[u8]) -> Vec<u8> {
+    const ENGINE: GeneralPurpose = GeneralPurpose::new(
+        &base64::alphabet::STANDARD,
+        GeneralPurposeConfig::new()
+            .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+            .with_decode_allow_trailing_bits(true),
+    );
+
+    let mut filtered: Vec<u8> = input
+        .iter()
+        .copied()
+        .filter(|&c| c.is_ascii_alphanumeric() || c == b'+' || c == b'/')
+        .collect();
+    // A single leftover character does not encode any complete byte.
+    if filtered.len() % 4 == 1 {
+        filtered.pop();
+    }
+
+    ENGINE.decode(filtered).unwrap_or_default()
+}
+
+/// Incremental base64 decoder.
+///
+/// Decodes a base64 body that arrives in chunks of arbitrary size. The
+/// output is the same as [`decode_base64`] on the whole input. At most
+/// three characters are held back between chunks.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2045::Base64Decoder;
+///
+/// let mut decoder = Base64Decoder::new();
+/// let mut out = decoder.update(b"aGVsb");
+/// out.extend(decoder.update(b"G8g\r\nd29ybGQ="));
+/// out.extend(decoder.finalize());
+/// assert_eq!(out, b"hello world");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Base64Decoder {
+    pending: Vec<u8>,
+}
+
+impl Base64Decoder {
+    /// Create a new decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the next chunk of input.
+    pub fn update(&mut self, input: &[u8]) -> Vec<u8> {
+        self.pending.extend(
+            input
+                .iter()
+                .filter(|&&c| c.is_ascii_alphanumeric() || c == b'+' || c == b'/'),
+        );
+        let end = self.pending.len() - self.pending.len() % 4;
+        let out = decode_base64(&self.pending[..end]);
+        self.pending.drain(..end);
+
+        out
+    }
+
+    /// Decode the input that was held back.
+    pub fn finalize(self) -> Vec<u8> {
+        decode_base64(&self.pending)
+    }
+}
+
+/// A decoded MIME body.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedBody {
+    /// The body of a `text/*` part decoded using its charset.
+    Text(String),
+    /// The body of any other part.
+    Binary(Vec<u8>),
+}
+
+/// Decode the body of a single part message.
+///
+/// The `"Content-Transfer-Encoding"` is undone. For `text/*` parts the
+/// result is then decoded to a string using the `charset` parameter.
+/// Missing or invalid headers take their default values from RFC 2045
+/// which is `text/plain; charset=us-ascii` and `7bit`. Unknown
+/// charsets are decoded as UTF-8.
+///
+/// # Examples
+/// ```
+/// use rustyknife::headersection::header_section;
+/// use rustyknife::rfc2045::{decode_body, DecodedBody};
+///
+/// let message = b"Content-Type: text/plain; charset=iso-8859-1\r\nContent-Transfer-Encoding: quoted-printable\r\n\r\ncaf=E9\r\n";
+/// let (body, headers) = header_section(message).unwrap();
+/// assert_eq!(decode_body(&headers, body), DecodedBody::Text("café\r\n".into()));
+/// ```
+pub fn decode_body(headers: &[HeaderField<'_>], body: &[u8]) -> DecodedBody {
+    let find = |name: &str| {
+        headers
+            .iter()
+            .flatten()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.as_bytes()))
+            .map(|(_, v)| *v)
+    };
+
+    let (mime_type, params) = find("content-type")
+        .and_then(|v| content_type(v).ok())
+        .map(|(_, ct)| ct)
+        .unwrap_or_else(|| ("text/plain".into(), Vec::new()));
+    let cte = find("content-transfer-encoding")
+        .and_then(|v| content_transfer_encoding(v).ok())
+        .map(|(_, cte)| cte);
+
+    let bytes = match cte {
+        Some(ContentTransferEncoding::QuotedPrintable) => decode_quoted_printable(body),
+        Some(ContentTransferEncoding::Base64) => decode_base64(body),
+        _ => body.to_vec(),
+    };
+
+    if !mime_type.starts_with("text/") {
+        return DecodedBody::Binary(bytes);
+    }
+
+    let encoding = match params.iter().find(|(n, _)| n == "charset") {
+        Some((_, charset)) => Encoding::for_label(charset.as_bytes()).unwrap_or(encoding_rs::UTF_8),
+        // us-ascii, which encoding_rs maps to windows-1252.
+        None => WINDOWS_1252,
+    };
+
+    DecodedBody::Text(encoding.decode_without_bom_handling(&bytes).0.into_owned())
+}
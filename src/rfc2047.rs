@@ -2,12 +2,16 @@
 //!
 //! [Header extensions for non-ASCII text]: https://tools.ietf.org/html/rfc2047
 
+use crate::headersection::fold_line;
+use crate::headersection::HeaderField;
+use crate::rfc2231::is_token_char;
 use crate::rfc3461::hexpair;
 use crate::util::*;
 use base64::Engine as _;
 use encoding_rs::{Encoding, UTF_8}; // TODO: was ASCII
 use nom::branch::alt;
 use nom::bytes::complete::tag;
+use nom::bytes::complete::tag_no_case;
 use nom::bytes::complete::take_while1;
 use nom::combinator::all_consuming;
 use nom::combinator::map;
@@ -18,9 +22,17 @@ use nom::sequence::preceded;
 use nom::sequence::terminated;
 use nom::sequence::tuple;
 use std::borrow::Cow;
+use std::str;
 
+// RFC 2047 tokens also exclude ".".
 fn token(input: &[u8]) -> NomResult<'_, &[u8]> {
-    take_while1(|c: u8| c.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?.=".contains(&c))(input)
+    take_while1(|c: u8| is_token_char(c) && c != b'.')(input)
+}
+
+// Real charset labels such as "ansi_x3.4-1968" contain a ".", so
+// accept it here. "*" introduces the RFC 2231 language.
+fn charset_token(input: &[u8]) -> NomResult<'_, &[u8]> {
+    take_while1(|c: u8| is_token_char(c) && c != b'*')(input)
 }
 
 fn encoded_text(input: &[u8]) -> NomResult<'_, &[u8]> {
@@ -40,11 +52,26 @@ fn decode_qp(input: &[u8]) -> Option<Vec<u8>> {
     all_consuming(_qp_encoded_text)(input).ok().map(|(_, o)| o)
 }
 
+// Whether every "=" in quoted-printable text starts a hex escape.
+// The lenient decoder keeps a stray "=" as is.
+fn _is_strict_qp(text: &[u8]) -> bool {
+    let mut rem = text;
+
+    while let Some(pos) = rem.iter().position(|&c| c == b'=') {
+        match rem.get(pos + 1..pos + 3) {
+            Some(hex) if hex.iter().all(u8::is_ascii_hexdigit) => rem = &rem[pos + 3..],
+            _ => return false,
+        }
+    }
+
+    true
+}
+
 // Undoes the quoted-printable or base64 encoding.
 fn decode_text(encoding: &[u8], text: &[u8]) -> Option<Vec<u8>> {
     match encoding {
         [b'q' | b'Q'] => decode_qp(text),
-        [b'b' | b'B'] => base64::engine::general_purpose::STANDARD.decode(text).ok(),
+        [b'b' | b'B'] => decode_base64_tolerant(text),
         _ => None,
     }
 }
@@ -61,30 +88,493 @@ fn decode_text(encoding: &[u8], text: &[u8]) -> Option<Vec<u8>> {
 pub fn encoded_word(input: &[u8]) -> NomResult<'_, EncodedWord<'_>> {
     map(
         tuple((
-            preceded(tag("=?"), token),
+            preceded(tag("=?"), charset_token),
             opt(preceded(tag("*"), token)),
             delimited(tag("?"), token, tag("?")),
             terminated(encoded_text, tag("?=")),
         )),
-        |(charset, _lang, encoding, text)| EncodedWord {
-            charset: charset::decode_ascii(charset),
-            bytes: decode_text(encoding, text).unwrap_or_else(|| text.to_vec()),
+        |(charset, lang, encoding, text)| {
+            let bytes = decode_text(encoding, text);
+            let is_qp = matches!(encoding, [b'q' | b'Q']);
+
+            EncodedWord {
+                charset: charset::decode_ascii(charset),
+                charset_raw: charset,
+                language: lang.map(|l| str::from_utf8(l).unwrap()),
+                valid_encoding: bytes.is_some() && (!is_qp || _is_strict_qp(text)),
+                bytes: bytes.unwrap_or_else(|| text.to_vec()),
+            }
         },
     )(input)
 }
 
+// Whether `input` starts like an encoded word, up to and including the
+// "?" after the encoding, without being a valid one.
+pub(crate) fn is_unterminated_encoded_word(input: &[u8]) -> bool {
+    let mut prefix = tuple((
+        tag("=?"),
+        charset_token,
+        opt(preceded(tag("*"), token)),
+        tag("?"),
+        alt((tag_no_case("b"), tag_no_case("q"))),
+        tag("?"),
+    ));
+
+    prefix(input).is_ok() && encoded_word(input).is_err()
+}
+
 /// An encoded word. Constructed by [`encoded_word`].
 #[derive(Debug)]
 pub struct EncodedWord<'a> {
     charset: Cow<'a, str>,
+    charset_raw: &'a [u8],
+    language: Option<&'a str>,
+    valid_encoding: bool,
     bytes: Vec<u8>,
 }
 
-impl EncodedWord<'_> {
+/// Why [`EncodedWord::try_decode`] failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The charset label is not known.
+    UnknownCharset,
+    /// The encoding is neither `"B"` nor `"Q"` or the encoded text is
+    /// not valid for it.
+    InvalidEncoding,
+    /// The decoded bytes are not valid in the declared charset.
+    MalformedText,
+}
+
+impl<'a> EncodedWord<'a> {
+    /// The charset label exactly as declared in the encoded word.
+    ///
+    /// Useful for diagnosing why a charset lookup failed.
+    pub fn charset_raw(&self) -> &'a [u8] {
+        self.charset_raw
+    }
+
+    /// The declared charset label.
+    ///
+    /// The label is returned as written, without checking that it is
+    /// a known charset.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::rfc2047::encoded_word;
+    ///
+    /// let (_, word) = encoded_word(b"=?x-unknown?q?abc?=").unwrap();
+    /// assert_eq!(word.charset(), "x-unknown");
+    /// ```
+    pub fn charset(&self) -> &str {
+        &self.charset
+    }
+
+    /// The [RFC 2231] language tag following the charset, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::rfc2047::encoded_word;
+    ///
+    /// let (_, word) = encoded_word(b"=?us-ascii*en-US?q?Keith_Moore?=").unwrap();
+    /// assert_eq!(word.charset(), "us-ascii");
+    /// assert_eq!(word.language(), Some("en-US"));
+    /// ```
+    ///
+    /// [RFC 2231]: https://tools.ietf.org/html/rfc2231#section-5
+    pub fn language(&self) -> Option<&'a str> {
+        self.language
+    }
+
     pub fn decode(&self) -> Cow<'_, str> {
         Encoding::for_label(self.charset.as_bytes())
             .unwrap_or(UTF_8)
             .decode_without_bom_handling(&self.bytes)
             .0
     }
+
+    /// Decode the text, failing on anything that [`decode`](Self::decode)
+    /// would silently repair.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::rfc2047::{encoded_word, DecodeError};
+    ///
+    /// let (_, word) = encoded_word(b"=?utf-8?q?caf=C3=A9?=").unwrap();
+    /// assert_eq!(word.try_decode().unwrap(), "café");
+    ///
+    /// let (_, word) = encoded_word(b"=?x-unknown?q?abc?=").unwrap();
+    /// assert_eq!(word.try_decode(), Err(DecodeError::UnknownCharset));
+    /// assert_eq!(word.decode(), "abc");
+    /// ```
+    pub fn try_decode(&self) -> Result<Cow<'_, str>, DecodeError> {
+        let encoding =
+            Encoding::for_label(self.charset.as_bytes()).ok_or(DecodeError::UnknownCharset)?;
+
+        if !self.valid_encoding {
+            return Err(DecodeError::InvalidEncoding);
+        }
+
+        encoding
+            .decode_without_bom_handling_and_without_replacement(&self.bytes)
+            .ok_or(DecodeError::MalformedText)
+    }
+
+    /// Whether this word encodes text that did not need encoding.
+    ///
+    /// Returns `true` if the decoded text is non-empty printable ASCII
+    /// that [`header_needs_encoding`] would have left as is. Encoding
+    /// plain text like this is a common way to hide keywords from
+    /// filters, so spam scoring may weight it.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::rfc2047::encoded_word;
+    ///
+    /// let (_, word) = encoded_word(b"=?utf-8?b?RlJFRSBWSUFHUkE=?=").unwrap();
+    /// assert!(word.is_gratuitous());
+    ///
+    /// let (_, word) = encoded_word(b"=?utf-8?q?caf=C3=A9?=").unwrap();
+    /// assert!(!word.is_gratuitous());
+    /// ```
+    pub fn is_gratuitous(&self) -> bool {
+        let text = self.decode();
+        !text.is_empty() && !header_needs_encoding(&text)
+    }
+}
+
+/// Decode every encoded word found in `input`.
+///
+/// Encoded words are recognized anywhere in the input and the text
+/// around them is kept as is, with invalid UTF-8 replaced. As in [RFC
+/// 2047 section 6.2], whitespace between two adjacent encoded words is
+/// dropped. Unlike [`unstructured`](crate::rfc5322::unstructured), no
+/// header syntax such as folding is interpreted.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2047::decode_rfc2047;
+///
+/// let decoded = decode_rfc2047(b"Re: =?utf-8?q?caf=C3=A9?=  =?iso-8859-1?q?_cr=E8me?= (2)");
+/// assert_eq!(decoded, "Re: café crème (2)");
+/// ```
+///
+/// [RFC 2047 section 6.2]: https://tools.ietf.org/html/rfc2047#section-6.2
+pub fn decode_rfc2047(input: &[u8]) -> String {
+    let mut out = String::new();
+    let mut literal_start = 0;
+    let mut pos = 0;
+    let mut after_word = false;
+
+    while let Some(found) = input[pos..].windows(2).position(|w| w == b"=?") {
+        let start = pos + found;
+
+        match encoded_word(&input[start..]) {
+            Ok((rem, word)) => {
+                let literal = &input[literal_start..start];
+                if !(after_word && literal.iter().all(u8::is_ascii_whitespace)) {
+                    out.push_str(&String::from_utf8_lossy(literal));
+                }
+                out.push_str(&word.decode());
+
+                after_word = true;
+                pos = input.len() - rem.len();
+                literal_start = pos;
+            }
+            Err(_) => pos = start + 2,
+        }
+    }
+    out.push_str(&String::from_utf8_lossy(&input[literal_start..]));
+
+    out
+}
+
+/// Check whether a header value must be encoded to be transmitted.
+///
+/// Returns `true` if `value` contains anything other than printable
+/// ASCII, space and tab, such as control characters or non-ASCII
+/// text. Text containing `"=?"` also needs encoding since it could
+/// otherwise be mistaken for an encoded word.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2047::header_needs_encoding;
+///
+/// assert!(!header_needs_encoding("Hello, world!"));
+/// assert!(header_needs_encoding("Café"));
+/// assert!(header_needs_encoding("line\r\nbreak"));
+/// ```
+pub fn header_needs_encoding(value: &str) -> bool {
+    value.contains("=?")
+        || value
+            .bytes()
+            .any(|c| !(c.is_ascii_graphic() || c == b' ' || c == b'\t'))
+}
+
+/// Normalize decoded header text for full-text search indexing.
+///
+/// Takes text that has already been decoded, such as the output of
+/// [`unstructured`](crate::rfc5322::unstructured), and returns it case
+/// folded with runs of whitespace collapsed to a single space and
+/// leading and trailing whitespace removed.
+///
+/// Case folding is done by lowercasing, with `ß` folded to `ss` and
+/// final sigma to `σ` as in Unicode full case folding.
+///
+/// With the `unicode-normalization` feature, the text is also put in
+/// compatibility decomposed form and diacritics are stripped, so that
+/// `"Café"` and `"CAFE"` give the same result.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2047::normalize_for_search;
+///
+/// assert_eq!(normalize_for_search("  Re:\tGROẞE  Straße "), "re: grosse strasse");
+/// ```
+pub fn normalize_for_search(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+
+    for word in value.split_whitespace() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        for c in _strip_diacritics(word) {
+            match c {
+                'ß' | 'ẞ' => out.push_str("ss"),
+                'ς' => out.push('σ'),
+                _ => out.extend(c.to_lowercase()),
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(feature = "unicode-normalization")]
+fn _strip_diacritics(word: &str) -> impl Iterator<Item = char> + '_ {
+    use unicode_normalization::char::is_combining_mark;
+    use unicode_normalization::UnicodeNormalization;
+
+    word.nfkd().filter(|&c| !is_combining_mark(c))
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+fn _strip_diacritics(word: &str) -> impl Iterator<Item = char> + '_ {
+    word.chars()
+}
+
+/// The encoding used for the text of an encoded word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordEncoding {
+    /// The `"B"` encoding, which is base64.
+    B,
+    /// The `"Q"` encoding, which is similar to quoted-printable.
+    Q,
+}
+
+impl WordEncoding {
+    // Length of `bytes` once encoded.
+    fn encoded_len(self, bytes: &[u8]) -> usize {
+        match self {
+            WordEncoding::B => bytes.len().div_ceil(3) * 4,
+            WordEncoding::Q => bytes
+                .iter()
+                .map(|&c| if _is_q_literal(c) { 1 } else { 3 })
+                .sum(),
+        }
+    }
+
+    fn encode(self, bytes: &[u8], out: &mut String) {
+        match self {
+            WordEncoding::B => base64::engine::general_purpose::STANDARD.encode_string(bytes, out),
+            WordEncoding::Q => {
+                for &c in bytes {
+                    match c {
+                        b' ' => out.push('_'),
+                        _ if _is_q_literal(c) => out.push(char::from(c)),
+                        _ => out.push_str(&format!("={:02X}", c)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Characters left as is by the "Q" encoding. This is the restricted
+// set allowed in a phrase so that the words can be used anywhere.
+fn _is_q_literal(c: u8) -> bool {
+    c == b' ' || c.is_ascii_alphanumeric() || b"!*+-/".contains(&c)
+}
+
+/// Encode text as encoded words.
+///
+/// The text is converted to `charset` and split on character
+/// boundaries into words of at most 75 characters, separated by
+/// spaces. If `charset` cannot represent all of the text, UTF-8 is
+/// used instead. Returns an empty string for empty text.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2047::{encode_word, encoded_word, WordEncoding};
+/// use rustyknife::encoding_rs::WINDOWS_1252;
+///
+/// let encoded = encode_word("Café au lait", WINDOWS_1252, WordEncoding::Q);
+/// assert_eq!(encoded, "=?windows-1252?q?Caf=E9_au_lait?=");
+/// assert_eq!(encoded_word(encoded.as_bytes()).unwrap().1.decode(), "Café au lait");
+/// ```
+pub fn encode_word(text: &str, charset: &'static Encoding, encoding: WordEncoding) -> String {
+    _encode_words_sized(text, charset, encoding, MAX_WORD).join(" ")
+}
+
+// The longest allowed encoded word.
+const MAX_WORD: usize = 75;
+
+// Encode text as encoded words, the first of which is at most
+// `first_max` characters long.
+fn _encode_words_sized(
+    text: &str,
+    charset: &'static Encoding,
+    encoding: WordEncoding,
+    first_max: usize,
+) -> Vec<String> {
+    let charset = match charset.encode(text) {
+        (_, output, false) => output,
+        (_, _, true) => UTF_8,
+    };
+    let label = charset.name().to_ascii_lowercase();
+    let prefix = match encoding {
+        WordEncoding::B => format!("=?{}?b?", label),
+        WordEncoding::Q => format!("=?{}?q?", label),
+    };
+    let word_len = |chunk: &str| prefix.len() + encoding.encoded_len(&charset.encode(chunk).0) + 2;
+
+    let mut out = Vec::new();
+    let mut push_word = |chunk: &str| {
+        let mut word = prefix.clone();
+        encoding.encode(&charset.encode(chunk).0, &mut word);
+        word.push_str("?=");
+        out.push(word);
+    };
+
+    let mut start = 0;
+    let mut max = first_max;
+    for (pos, c) in text.char_indices() {
+        if pos > start && word_len(&text[start..pos + c.len_utf8()]) > max {
+            push_word(&text[start..pos]);
+            start = pos;
+            max = MAX_WORD;
+        }
+    }
+    if start < text.len() {
+        push_word(&text[start..]);
+    }
+
+    out
+}
+
+/// Encode text as UTF-8 encoded words only if needed.
+///
+/// Text that [`header_needs_encoding`] would leave as is is returned
+/// unchanged. Otherwise the shorter of the `"Q"` and `"B"` encodings
+/// is used, which is `"Q"` for mostly ASCII text and `"B"` for mostly
+/// non-ASCII text. See [`encode_word`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2047::encode_word_auto;
+///
+/// assert_eq!(encode_word_auto("Hello"), "Hello");
+/// assert_eq!(encode_word_auto("Café au lait"), "=?utf-8?q?Caf=C3=A9_au_lait?=");
+/// assert_eq!(encode_word_auto("日本語"), "=?utf-8?b?5pel5pys6Kqe?=");
+/// ```
+pub fn encode_word_auto(text: &str) -> String {
+    if !header_needs_encoding(text) {
+        return text.into();
+    }
+
+    let q = encode_word(text, UTF_8, WordEncoding::Q);
+    let b = encode_word(text, UTF_8, WordEncoding::B);
+    if q.len() <= b.len() {
+        q
+    } else {
+        b
+    }
+}
+
+/// Serialize a header line with encoded text, including the trailing
+/// CRLF.
+///
+/// The text is encoded as by [`encode_word_auto`] and folded between
+/// encoded words so that no line exceeds the 76 character limit of
+/// [RFC 2047]. The first word is shortened to leave room for the header
+/// name. Text that needs no encoding is folded at its spaces.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2047::fold_header;
+///
+/// assert_eq!(
+///     fold_header("Subject", &"日本語".repeat(8)),
+///     "Subject: =?utf-8?b?5pel5pys6Kqe5pel5pys6Kqe5pel5pys6Kqe5pel5pys6Kqe5pel?=\r\n \
+///      =?utf-8?b?5pys6Kqe5pel5pys6Kqe5pel5pys6Kqe5pel5pys6Kqe?=\r\n"
+/// );
+/// ```
+///
+/// [RFC 2047]: https://tools.ietf.org/html/rfc2047#section-2
+pub fn fold_header(name: &str, text: &str) -> String {
+    fold_encoded(name, text, 76)
+}
+
+// Write "name: text" followed by CRLF, encoding the text as by
+// `encode_word_auto` and folding between the encoded words so that
+// lines stay within `max_line` columns.
+pub(crate) fn fold_encoded(name: &str, text: &str, max_line: usize) -> String {
+    if !header_needs_encoding(text) {
+        return fold_line(name, text, max_line);
+    }
+
+    let first_max = max_line.saturating_sub(name.len() + 2);
+    let words = [WordEncoding::Q, WordEncoding::B]
+        .iter()
+        .map(|&e| _encode_words_sized(text, UTF_8, e, first_max))
+        .min_by_key(|words| words.iter().map(String::len).sum::<usize>())
+        .unwrap();
+
+    format!("{}: {}\r\n", name, words.join("\r\n "))
+}
+
+/// List the charsets declared by encoded words in a header section.
+///
+/// Every valid header value is scanned for encoded words and the
+/// distinct charset labels are returned lowercased, in order of first
+/// occurrence. Nothing is decoded beyond what is needed to recognize
+/// the encoded words.
+/// # Examples
+/// ```
+/// use rustyknife::headersection::header_section;
+/// use rustyknife::rfc2047::header_charsets;
+///
+/// let (_, fields) = header_section(b"Subject: =?UTF-8?Q?caf=C3=A9?= =?iso-8859-1?Q?=E9?=\r\nFrom: =?utf-8?B?w6k=?= <a@example.org>\r\n\r\n").unwrap();
+/// assert_eq!(header_charsets(&fields), ["utf-8", "iso-8859-1"]);
+/// ```
+pub fn header_charsets(fields: &[HeaderField<'_>]) -> Vec<String> {
+    let mut out = Vec::new();
+
+    for (_, value) in fields.iter().flatten() {
+        let mut rem = *value;
+
+        while let Some(pos) = rem.windows(2).position(|w| w == b"=?") {
+            match encoded_word(&rem[pos..]) {
+                Ok((next, word)) => {
+                    let charset = word.charset.to_lowercase();
+                    if !out.contains(&charset) {
+                        out.push(charset);
+                    }
+                    rem = next;
+                }
+                Err(_) => rem = &rem[pos + 2..],
+            }
+        }
+    }
+
+    out
 }
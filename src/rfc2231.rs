@@ -7,8 +7,11 @@
 
 use crate::rfc3461::hexpair;
 use crate::rfc5234::crlf;
+use crate::rfc5322::date;
 use crate::rfc5322::ofws;
 use crate::rfc5322::quoted_string;
+use crate::rfc5322::DateTimeFields;
+use crate::types::QuotedString;
 use crate::util::*;
 use charset::decode_ascii;
 use encoding_rs::Encoding;
@@ -19,6 +22,7 @@ use nom::bytes::complete::tag_no_case;
 use nom::bytes::complete::take_while1;
 use nom::bytes::complete::take_while_m_n;
 use nom::combinator::map;
+use nom::combinator::map_opt;
 use nom::combinator::opt;
 use nom::combinator::recognize;
 use nom::combinator::verify;
@@ -28,8 +32,13 @@ use nom::sequence::pair;
 use nom::sequence::preceded;
 use nom::sequence::separated_pair;
 use nom::sequence::terminated;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::fmt::{self};
 use std::str;
@@ -56,6 +65,7 @@ enum Value<'a> {
 enum ExtendedValue<'a> {
     Initial {
         encoding: Option<&'a [u8]>,
+        #[allow(dead_code)]
         language: Option<&'a [u8]>,
         value: Vec<u8>,
     },
@@ -80,6 +90,36 @@ fn regular_parameter(input: &[u8]) -> NomResult<'_, Parameter<'_>> {
     )(input)
 }
 
+// Some webmail clients quote values with U+201C and U+201D instead of
+// ASCII double quotes.
+fn smart_quoted_parameter(input: &[u8]) -> NomResult<'_, Parameter<'_>> {
+    map(
+        separated_pair(regular_parameter_name, _equals, smart_quoted_value),
+        |(name, value)| Parameter {
+            name,
+            value: Value::Regular(value),
+        },
+    )(input)
+}
+
+fn smart_quote(input: &[u8]) -> NomResult<'_, &[u8]> {
+    alt((tag("\u{201C}"), tag("\u{201D}")))(input)
+}
+
+fn smart_quoted_value(input: &[u8]) -> NomResult<'_, Cow<'_, str>> {
+    let (rem, _) = smart_quote(input)?;
+    let end = (0..rem.len())
+        .find(|&i| smart_quote(&rem[i..]).is_ok())
+        .ok_or(nom::Err::Error(()))?;
+    let value = std::str::from_utf8(&rem[..end]).map_err(|_| nom::Err::Error(()))?;
+    if value.contains(['\r', '\n']) {
+        return Err(nom::Err::Error(()));
+    }
+    let (rem, _) = smart_quote(&rem[end..])?;
+
+    Ok((rem, Cow::Borrowed(value)))
+}
+
 fn regular_parameter_name(input: &[u8]) -> NomResult<'_, Name<'_>> {
     map(pair(attribute, opt(section)), |(name, section)| Name {
         name: std::str::from_utf8(name).unwrap(),
@@ -87,15 +127,33 @@ fn regular_parameter_name(input: &[u8]) -> NomResult<'_, Name<'_>> {
     })(input)
 }
 
-fn token(input: &[u8]) -> NomResult<'_, &str> {
-    map(
-        take_while1(|c| (33..=126).contains(&c) && !b"()<>@,;:\\\"/[]?=".contains(&c)),
-        |t| std::str::from_utf8(t).unwrap(),
-    )(input)
+pub(crate) fn is_token_char(c: u8) -> bool {
+    (33..=126).contains(&c) && !b"()<>@,;:\\\"/[]?=".contains(&c)
+}
+
+/// Parse an [RFC 2045] `token`.
+///
+/// A token is one or more printable ASCII characters, excluding space
+/// and the `tspecials`: `()<>@,;:\"/[]?=`. This is the syntax of MIME
+/// types, subtypes, parameter names and unquoted parameter values.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::mime_token;
+///
+/// assert_eq!(mime_token(b"x-zip-compressed; name=a"), Ok((&b"; name=a"[..], "x-zip-compressed")));
+/// assert!(mime_token(b" text").is_err());
+/// ```
+///
+/// [RFC 2045]: https://tools.ietf.org/html/rfc2045#section-5.1
+pub fn mime_token(input: &[u8]) -> NomResult<'_, &str> {
+    map(take_while1(is_token_char), |t| {
+        std::str::from_utf8(t).unwrap()
+    })(input)
 }
 
-fn is_attribute_char(c: u8) -> bool {
-    (33..=126).contains(&c) && !b"*'%()<>@,;:\\\"/[]?=".contains(&c)
+pub(crate) fn is_attribute_char(c: u8) -> bool {
+    is_token_char(c) && !b"*'%".contains(&c)
 }
 
 fn attribute_char(input: &[u8]) -> NomResult<'_, u8> {
@@ -115,7 +173,7 @@ fn initial_section(input: &[u8]) -> NomResult<'_, u32> {
 }
 
 fn other_sections(input: &[u8]) -> NomResult<'_, u32> {
-    map(
+    map_opt(
         preceded(
             tag("*"),
             verify(
@@ -123,7 +181,7 @@ fn other_sections(input: &[u8]) -> NomResult<'_, u32> {
                 |x: &[u8]| x[0] != b'0',
             ),
         ),
-        |s| str::from_utf8(s).unwrap().parse().unwrap(),
+        |s| parse_u64_bounded(s, u32::MAX.into()).map(|n| n as u32),
     )(input)
 }
 
@@ -191,7 +249,7 @@ fn extended_other_values(input: &[u8]) -> NomResult<'_, Vec<u8>> {
 
 fn value(input: &[u8]) -> NomResult<'_, Cow<'_, str>> {
     alt((
-        map(token, Cow::from),
+        map(mime_token, Cow::from),
         map(quoted_string::<crate::behaviour::Intl>, |qs| {
             Cow::from(qs.0)
         }),
@@ -199,14 +257,73 @@ fn value(input: &[u8]) -> NomResult<'_, Cow<'_, str>> {
 }
 
 fn _mime_type(input: &[u8]) -> NomResult<'_, &[u8]> {
-    recognize(tuple((token, tag("/"), token)))(input)
+    recognize(tuple((mime_token, tag("/"), mime_token)))(input)
 }
 
-fn _parameter_list(input: &[u8]) -> NomResult<'_, Vec<Parameter<'_>>> {
-    terminated(
-        many0(preceded(pair(tag(";"), ofws), parameter)),
-        pair(opt(tag(";")), opt(crlf)),
-    )(input)
+/// Options for parsing MIME parameters.
+///
+/// The default is strict parsing with at most
+/// [`DEFAULT_MAX_SEGMENTS`](Self::DEFAULT_MAX_SEGMENTS) continuation
+/// segments per parameter.
+#[derive(Clone, Copy, Debug)]
+pub struct ParameterOptions {
+    /// Accept parameters separated only by whitespace, such as
+    /// `"text/plain charset=utf-8"`. Some Microsoft tools omit the `;`.
+    ///
+    /// Also accept values quoted with the Unicode quotation marks
+    /// `“` and `”`, as sent by some webmail clients.
+    pub lenient: bool,
+    /// Fail if a parameter is repeated, such as
+    /// `"charset=utf-8; charset=iso-8859-1"`. Otherwise the first
+    /// occurrence wins.
+    pub reject_duplicates: bool,
+    /// The maximum number of [RFC 2231] continuation segments such as
+    /// `filename*0`, `filename*1`, ... in a single parameter. A
+    /// parameter with more segments is dropped rather than
+    /// reassembled, which bounds the work done on malicious input.
+    ///
+    /// [RFC 2231]: https://tools.ietf.org/html/rfc2231#section-3
+    pub max_segments: usize,
+}
+
+impl ParameterOptions {
+    /// The default for [`max_segments`](Self::max_segments).
+    pub const DEFAULT_MAX_SEGMENTS: usize = 256;
+}
+
+impl Default for ParameterOptions {
+    fn default() -> Self {
+        ParameterOptions {
+            lenient: false,
+            reject_duplicates: false,
+            max_segments: Self::DEFAULT_MAX_SEGMENTS,
+        }
+    }
+}
+
+fn _parameter_list(opts: ParameterOptions) -> impl Fn(&[u8]) -> NomResult<'_, Vec<Parameter<'_>>> {
+    move |input| {
+        let separator = |i| {
+            if opts.lenient {
+                alt((recognize(pair(tag(";"), ofws)), recognize(ofws)))(i)
+            } else {
+                recognize(pair(tag(";"), ofws))(i)
+            }
+        };
+
+        let param = |i| {
+            if opts.lenient {
+                alt((parameter, smart_quoted_parameter))(i)
+            } else {
+                parameter(i)
+            }
+        };
+
+        terminated(
+            many0(preceded(separator, param)),
+            pair(opt(tag(";")), opt(crlf)),
+        )(input)
+    }
 }
 
 #[derive(Debug)]
@@ -216,7 +333,7 @@ enum Segment<'a> {
 }
 
 fn decode_segments(mut input: Vec<(u32, Segment<'_>)>, encoding: &'static Encoding) -> String {
-    input.sort_by(|a, b| a.0.cmp(&b.0));
+    input.sort_by_key(|a| a.0);
     let mut out = String::new();
     let mut encoded = Vec::new();
 
@@ -240,14 +357,49 @@ fn decode_segments(mut input: Vec<(u32, Segment<'_>)>, encoding: &'static Encodi
     out
 }
 
-fn decode_parameter_list(input: Vec<Parameter<'_>>) -> Vec<(String, String)> {
+// Duplicate parameters are invalid. The first occurrence of each
+// parameter and section wins unless duplicates are rejected.
+fn decode_parameter_list(
+    input: Vec<Parameter<'_>>,
+    opts: ParameterOptions,
+) -> Option<Vec<(String, String)>> {
+    decode_parameter_list_ordered(input, opts).map(|(params, _)| params)
+}
+
+// Returns the parameters in the order their names first appear,
+// along with the names that were repeated.
+#[allow(clippy::type_complexity)]
+fn decode_parameter_list_ordered(
+    input: Vec<Parameter<'_>>,
+    opts: ParameterOptions,
+) -> Option<(Vec<(String, String)>, Vec<String>)> {
     let mut simple = HashMap::<String, String>::new();
     let mut simple_encoded = HashMap::<String, String>::new();
     let mut composite = HashMap::<String, Vec<(u32, Segment<'_>)>>::new();
     let mut composite_encoding = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut oversized = HashSet::new();
+    let mut order = Vec::new();
+    let mut ordered = HashSet::new();
+    let mut duplicates = Vec::new();
+    let mut duplicated = HashSet::new();
 
     for Parameter { name, value } in input {
         let name_norm = name.name.to_lowercase();
+        let extended = matches!(value, Value::Extended(_));
+
+        if !seen.insert((name_norm.clone(), name.section, extended)) {
+            if opts.reject_duplicates {
+                return None;
+            }
+            if duplicated.insert(name_norm.clone()) {
+                duplicates.push(name_norm);
+            }
+            continue;
+        }
+        if ordered.insert(name_norm.clone()) {
+            order.push(name_norm.clone());
+        }
 
         match name.section {
             None => {
@@ -279,7 +431,16 @@ fn decode_parameter_list(input: Vec<Parameter<'_>>) -> Vec<(String, String)> {
                 }
             }
             Some(section) => {
+                if oversized.contains(&name_norm) {
+                    continue;
+                }
                 let ent = composite.entry(name_norm.clone()).or_default();
+                if ent.len() >= opts.max_segments {
+                    composite.remove(&name_norm);
+                    composite_encoding.remove(&name_norm);
+                    oversized.insert(name_norm);
+                    continue;
+                }
 
                 match value {
                     Value::Regular(v) => ent.push((section, Segment::Decoded(v))),
@@ -305,35 +466,321 @@ fn decode_parameter_list(input: Vec<Parameter<'_>>) -> Vec<(String, String)> {
         }
     }
 
-    let mut composite_out = Vec::new();
     for (name, segments) in composite {
         let codec = composite_encoding.get(&name).cloned().unwrap_or(UTF_8);
-        composite_out.push((name, decode_segments(segments, codec)));
+        simple_encoded.insert(name, decode_segments(segments, codec));
     }
 
-    for (name, value) in simple_encoded.into_iter().chain(composite_out.into_iter()) {
-        simple.insert(name, value);
-    }
+    let params = order
+        .into_iter()
+        .filter_map(|name| {
+            let value = simple_encoded
+                .remove(&name)
+                .or_else(|| simple.remove(&name))?;
+            Some((name, value))
+        })
+        .collect();
 
-    simple.into_iter().collect()
+    Some((params, duplicates))
 }
 
 /// Parse a MIME `"Content-Type"` header.
 ///
-/// Returns a tuple of the MIME type and parameters.
+/// Returns a tuple of the MIME type and parameters. If a parameter is
+/// repeated, the first occurrence is used. See
+/// [`ParameterOptions::reject_duplicates`] to fail instead.
+///
+/// With the `quoted-string-rfc2047` feature, [RFC 2047] encoded words
+/// inside quoted parameter values are decoded. Some clients use these
+/// instead of RFC 2231 encoding for file names.
+///
+/// [RFC 2047]: https://tools.ietf.org/html/rfc2047
 pub fn content_type(input: &[u8]) -> NomResult<'_, (String, Vec<(String, String)>)> {
-    map(
-        pair(delimited(ofws, _mime_type, ofws), _parameter_list),
-        |(mt, p)| (decode_ascii(mt).to_lowercase(), decode_parameter_list(p)),
+    content_type_with(ParameterOptions::default())(input)
+}
+
+/// Parse a MIME `"Content-Type"` header with the given options.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::{content_type_with, ParameterOptions};
+///
+/// let opts = ParameterOptions {
+///     lenient: true,
+///     ..Default::default()
+/// };
+/// let (_, (_, params)) = content_type_with(opts)(b"text/plain charset=utf-8").unwrap();
+/// assert_eq!(params, [("charset".into(), "utf-8".into())]);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn content_type_with(
+    opts: ParameterOptions,
+) -> impl Fn(&[u8]) -> NomResult<'_, (String, Vec<(String, String)>)> {
+    move |input| {
+        map_opt(
+            pair(delimited(ofws, _mime_type, ofws), _parameter_list(opts)),
+            |(mt, p)| {
+                Some((
+                    decode_ascii(mt).to_lowercase(),
+                    decode_parameter_list(p, opts)?,
+                ))
+            },
+        )(input)
+    }
+}
+
+/// Parse a MIME `"Content-Type"` header, keeping parameter order.
+///
+/// Returns a tuple of the MIME type, the parameters in the order their
+/// names first appear, and the names of any parameters that were
+/// repeated. As with [`content_type`], the first occurrence of a
+/// repeated parameter is used.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::content_type_ordered;
+///
+/// let (_, (mime_type, params, duplicates)) =
+///     content_type_ordered(b"text/plain; format=flowed; charset=utf-8; format=fixed").unwrap();
+/// assert_eq!(mime_type, "text/plain");
+/// assert_eq!(
+///     params,
+///     [
+///         ("format".into(), "flowed".into()),
+///         ("charset".into(), "utf-8".into())
+///     ]
+/// );
+/// assert_eq!(duplicates, ["format"]);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn content_type_ordered(
+    input: &[u8],
+) -> NomResult<'_, (String, Vec<(String, String)>, Vec<String>)> {
+    let opts = ParameterOptions::default();
+
+    map_opt(
+        pair(delimited(ofws, _mime_type, ofws), _parameter_list(opts)),
+        move |(mt, p)| {
+            let (params, duplicates) = decode_parameter_list_ordered(p, opts)?;
+            Some((decode_ascii(mt).to_lowercase(), params, duplicates))
+        },
     )(input)
 }
 
+/// Encode a MIME parameter so that it parses back to the same value.
+///
+/// Produces `name=value` if the value is a token, `name="value"` if it
+/// is printable ASCII, and otherwise the [RFC 2231] extended form with
+/// the value encoded in `charset`. Values that would make the
+/// parameter longer than 76 characters are split into numbered
+/// continuations separated by `"; "`, so that a header can be folded
+/// to 78 columns. If `charset` is unknown or cannot represent the
+/// value, it is encoded as UTF-8 instead. The canonical name of the
+/// charset is written in lowercase.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::encode_param;
+///
+/// assert_eq!(encode_param("charset", "utf-8", "utf-8"), "charset=utf-8");
+/// assert_eq!(encode_param("name", "a b.txt", "utf-8"), "name=\"a b.txt\"");
+/// assert_eq!(encode_param("name", "café.txt", "latin2"), "name*=iso-8859-2''caf%E9.txt");
+/// ```
+///
+/// [RFC 2231]: https://tools.ietf.org/html/rfc2231
+pub fn encode_param(name: &str, value: &str, charset: &str) -> String {
+    const MAX_SEGMENT: usize = 76;
+
+    if !value.is_empty()
+        && value.bytes().all(is_token_char)
+        && name.len() + 1 + value.len() <= MAX_SEGMENT
+    {
+        return format!("{}={}", name, value);
+    }
+
+    let quoted = QuotedString(value.into()).quoted();
+    if value.bytes().all(|c| c.is_ascii_graphic() || c == b' ')
+        && name.len() + 1 + quoted.len() <= MAX_SEGMENT
+    {
+        return format!("{}={}", name, quoted);
+    }
+
+    // Fall back to UTF-8 if the charset is unknown or can't represent
+    // the value, so that it decodes back to the same text.
+    let encoding = match Encoding::for_label(charset.as_bytes()) {
+        Some(encoding) => match encoding.encode(value) {
+            (_, output, false) if output == encoding => encoding,
+            _ => UTF_8,
+        },
+        None => UTF_8,
+    };
+    let charset = encoding.name().to_ascii_lowercase();
+    let units: Vec<String> = encoding
+        .encode(value)
+        .0
+        .iter()
+        .map(|&c| {
+            if is_attribute_char(c) {
+                char::from(c).to_string()
+            } else {
+                format!("%{:02X}", c)
+            }
+        })
+        .collect();
+
+    let single = format!("{}*={}''{}", name, charset, units.concat());
+    if single.len() <= MAX_SEGMENT {
+        return single;
+    }
+
+    let mut out = String::new();
+    let mut segment = format!("{}*0*={}''", name, charset);
+    let mut segment_empty = true;
+    let mut section = 0;
+
+    for unit in units {
+        if !segment_empty && segment.len() + unit.len() > MAX_SEGMENT {
+            out.push_str(&segment);
+            out.push_str("; ");
+            section += 1;
+            segment = format!("{}*{}*=", name, section);
+        }
+        segment.push_str(&unit);
+        segment_empty = false;
+    }
+    out.push_str(&segment);
+
+    out
+}
+
+/// Encode the value of a MIME `"Content-Type"` header.
+///
+/// This is the inverse of [`content_type`]. Each parameter is encoded
+/// with [`encode_param`] using UTF-8 where the extended syntax is
+/// needed, and parameters are separated by `"; "`.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::encode_content_type;
+///
+/// let params = [("name".into(), "résumé.pdf".into())];
+/// assert_eq!(
+///     encode_content_type("application/pdf", &params),
+///     "application/pdf; name*=utf-8''r%C3%A9sum%C3%A9.pdf"
+/// );
+/// ```
+pub fn encode_content_type(mime_type: &str, params: &[(String, String)]) -> String {
+    let mut out = mime_type.to_string();
+
+    for (name, value) in params {
+        out.push_str("; ");
+        out.push_str(&encode_param(name, value, "utf-8"));
+    }
+
+    out
+}
+
+/// Sort parameters into a canonical order.
+///
+/// `boundary` comes first, followed by `charset`, then the remaining
+/// parameters ordered by name. Names are compared case-insensitively
+/// and parameters with the same name keep their relative order.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::{content_type, sort_params};
+///
+/// let (_, (_, mut params)) =
+///     content_type(b"multipart/mixed; Name=x; charset=utf-8; a=b; boundary=sep").unwrap();
+/// sort_params(&mut params);
+/// let names: Vec<_> = params.iter().map(|(n, _)| n.as_str()).collect();
+/// assert_eq!(names, ["boundary", "charset", "a", "name"]);
+/// ```
+pub fn sort_params(params: &mut [(String, String)]) {
+    params.sort_by_cached_key(|(name, _)| {
+        let name = name.to_lowercase();
+        let rank = match name.as_str() {
+            "boundary" => 0,
+            "charset" => 1,
+            _ => 2,
+        };
+        (rank, name)
+    });
+}
+
+// Parse a Content-Type into a form where equivalent values compare
+// equal.
+fn _normalized_content_type(
+    input: &[u8],
+    ignored: &[&str],
+) -> Option<(String, Vec<(String, String)>)> {
+    let (_, (mime_type, params)) = content_type(input).ok()?;
+    let mut params: Vec<_> = params
+        .into_iter()
+        .map(|(name, value)| (name.to_lowercase(), value))
+        .filter(|(name, _)| !ignored.iter().any(|i| i.eq_ignore_ascii_case(name)))
+        .map(|(name, value)| match name.as_str() {
+            "charset" => (name, value.to_lowercase()),
+            _ => (name, value),
+        })
+        .collect();
+    params.sort();
+
+    Some((mime_type, params))
+}
+
+/// Check whether two `"Content-Type"` values are equivalent.
+///
+/// The MIME types and parameter names are compared
+/// case-insensitively and parameter order does not matter, nor does
+/// quoting. The `charset` value is also compared case-insensitively.
+/// Other parameter values are compared exactly. Values that fail to
+/// parse are never equal.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::content_type_eq;
+///
+/// assert!(content_type_eq(b"Text/Plain; format=flowed; charset=UTF-8", b"text/plain; charset=\"utf-8\"; format=flowed"));
+/// assert!(!content_type_eq(b"text/plain", b"text/html"));
+/// ```
+pub fn content_type_eq(a: &[u8], b: &[u8]) -> bool {
+    content_type_eq_ignoring(a, b, &[])
+}
+
+/// Check whether two `"Content-Type"` values are equivalent, ignoring
+/// some parameters.
+///
+/// This is useful to compare the type identity of two parts without
+/// parameters such as `boundary` that differ between otherwise
+/// identical parts. See [`content_type_eq`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::content_type_eq_ignoring;
+///
+/// let a = b"multipart/mixed; boundary=a";
+/// let b = b"multipart/mixed; boundary=b";
+/// assert!(content_type_eq_ignoring(a, b, &["boundary"]));
+/// ```
+pub fn content_type_eq_ignoring(a: &[u8], b: &[u8], ignored: &[&str]) -> bool {
+    match (
+        _normalized_content_type(a, ignored),
+        _normalized_content_type(b, ignored),
+    ) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
 fn _x_token(input: &[u8]) -> NomResult<'_, &str> {
-    preceded(tag_no_case("x-"), token)(input)
+    preceded(tag_no_case("x-"), mime_token)(input)
 }
 
 /// Value from a MIME `"Content-Disposition"` header.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub enum ContentDisposition {
     /// "inline"
     Inline,
@@ -357,6 +804,12 @@ impl Display for ContentDisposition {
     }
 }
 
+impl From<ContentDisposition> for String {
+    fn from(value: ContentDisposition) -> String {
+        value.to_string()
+    }
+}
+
 fn _disposition(input: &[u8]) -> NomResult<'_, ContentDisposition> {
     alt((
         map(tag_no_case("inline"), |_| ContentDisposition::Inline),
@@ -364,24 +817,153 @@ fn _disposition(input: &[u8]) -> NomResult<'_, ContentDisposition> {
             ContentDisposition::Attachment
         }),
         map(_x_token, |x| ContentDisposition::Extended(x.into())),
-        map(token, |t| ContentDisposition::Token(t.into())),
+        map(mime_token, |t| ContentDisposition::Token(t.into())),
     ))(input)
 }
 
+nom_fromstr!(ContentDisposition, _disposition);
+
 /// Parse a MIME `"Content-Disposition"` header.
 ///
 /// Returns a tuple of [`ContentDisposition`] and parameters.
+///
+/// Quoted parameter values are decoded as for [`content_type`].
 pub fn content_disposition(
     input: &[u8],
 ) -> NomResult<'_, (ContentDisposition, Vec<(String, String)>)> {
-    map(
-        pair(delimited(ofws, _disposition, ofws), _parameter_list),
-        |(disp, p)| (disp, decode_parameter_list(p)),
-    )(input)
+    content_disposition_with(ParameterOptions::default())(input)
+}
+
+/// Parse a MIME `"Content-Disposition"` header with the given options.
+#[allow(clippy::type_complexity)]
+pub fn content_disposition_with(
+    opts: ParameterOptions,
+) -> impl Fn(&[u8]) -> NomResult<'_, (ContentDisposition, Vec<(String, String)>)> {
+    move |input| {
+        map_opt(
+            pair(delimited(ofws, _disposition, ofws), _parameter_list(opts)),
+            |(disp, p)| Some((disp, decode_parameter_list(p, opts)?)),
+        )(input)
+    }
+}
+
+/// Dates from the [RFC 2183] `"Content-Disposition"` parameters.
+///
+/// [RFC 2183]: https://tools.ietf.org/html/rfc2183
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DispositionDates {
+    /// The `creation-date` parameter.
+    pub creation: Option<DateTimeFields>,
+    /// The `modification-date` parameter.
+    pub modification: Option<DateTimeFields>,
+    /// The `read-date` parameter.
+    pub read: Option<DateTimeFields>,
+}
+
+/// Extract the [`DispositionDates`] from `"Content-Disposition"`
+/// parameters.
+///
+/// `params` is typically the output of [`content_disposition`]. Each
+/// value is parsed as an RFC 5322 date-time. Parameters that are
+/// missing or do not contain a valid date yield `None`.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::{content_disposition, disposition_dates};
+///
+/// let (_, (_, params)) = content_disposition(
+///     b"attachment; filename=a.txt; creation-date=\"Wed, 12 Feb 1997 16:29:51 -0500\"",
+/// )
+/// .unwrap();
+/// let dates = disposition_dates(&params);
+/// assert_eq!(dates.creation.unwrap().timestamp(), 855782991);
+/// assert_eq!(dates.modification, None);
+/// ```
+pub fn disposition_dates(params: &[(String, String)]) -> DispositionDates {
+    let parse = |value: &str| match date::<crate::behaviour::Intl>(value.as_bytes()) {
+        Ok(([], date)) => Some(date),
+        _ => None,
+    };
+    let mut out = DispositionDates::default();
+
+    for (name, value) in params {
+        let field = match name.to_lowercase().as_str() {
+            "creation-date" => &mut out.creation,
+            "modification-date" => &mut out.modification,
+            "read-date" => &mut out.read,
+            _ => continue,
+        };
+        *field = parse(value);
+    }
+
+    out
+}
+
+/// Reassembly parameters of a `message/partial` part.
+///
+/// See [RFC 2046 section 5.2.2].
+///
+/// [RFC 2046 section 5.2.2]: https://tools.ietf.org/html/rfc2046#section-5.2.2
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessagePartial {
+    /// Identifies the fragments of the same message.
+    pub id: String,
+    /// The number of this fragment, starting at 1.
+    pub number: u32,
+    /// The total number of fragments. Only required on the last one.
+    pub total: Option<u32>,
+}
+
+/// Extract the [`MessagePartial`] parameters from a `message/partial`
+/// `"Content-Type"`.
+///
+/// `params` is typically the output of [`content_type`]. Returns
+/// `None` if `id` or `number` is missing, if a number is invalid or if
+/// `total` is less than `number`.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::{content_type, message_partial_params};
+///
+/// let (_, (_, params)) = content_type(
+///     b"message/partial; id=\"ABC@host.com\"; number=2; total=3",
+/// )
+/// .unwrap();
+/// let partial = message_partial_params(&params).unwrap();
+/// assert_eq!(partial.id, "ABC@host.com");
+/// assert_eq!((partial.number, partial.total), (2, Some(3)));
+/// ```
+pub fn message_partial_params(params: &[(String, String)]) -> Option<MessagePartial> {
+    let get = |key: &str| {
+        params
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    };
+    let number = |value: &str| {
+        parse_u64_bounded(value.as_bytes(), u32::MAX.into())
+            .filter(|&n| n >= 1)
+            .map(|n| n as u32)
+    };
+
+    let id = get("id")?.to_string();
+    let num = number(get("number")?)?;
+    let total = match get("total") {
+        Some(value) => Some(number(value).filter(|&t| t >= num)?),
+        None => None,
+    };
+
+    Some(MessagePartial {
+        id,
+        number: num,
+        total,
+    })
 }
 
 /// Value from a MIME `"Content-Transfer-Encoding"` header.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub enum ContentTransferEncoding {
     /// "7bit"
     SevenBit,
@@ -414,6 +996,12 @@ impl Display for ContentTransferEncoding {
     }
 }
 
+impl From<ContentTransferEncoding> for String {
+    fn from(value: ContentTransferEncoding) -> String {
+        value.to_string()
+    }
+}
+
 use self::ContentTransferEncoding as CTE;
 use nom::sequence::tuple;
 
@@ -430,8 +1018,10 @@ pub fn content_transfer_encoding(input: &[u8]) -> NomResult<'_, ContentTransferE
             map(tag_no_case("base64"), |_| CTE::Base64),
             map(tag_no_case("quoted-printable"), |_| CTE::QuotedPrintable),
             map(_x_token, |x| CTE::Extended(x.into())),
-            map(token, |t| CTE::Token(t.into())),
+            map(mime_token, |t| CTE::Token(t.into())),
         )),
         ofws,
     )(input)
 }
+
+nom_fromstr!(ContentTransferEncoding, content_transfer_encoding);
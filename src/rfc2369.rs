@@ -0,0 +1,142 @@
+//! [Mailing list] header parsers
+//!
+//! [Mailing list]: https://tools.ietf.org/html/rfc2369
+
+use crate::behaviour::Intl;
+use crate::rfc5234::crlf;
+use crate::rfc5322::cfws;
+use crate::util::*;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::tag_no_case;
+use nom::bytes::complete::take_while;
+use nom::combinator::map;
+use nom::combinator::not;
+use nom::combinator::opt;
+use nom::multi::separated_list1;
+use nom::sequence::delimited;
+use nom::sequence::terminated;
+use std::fmt::Display;
+use std::fmt::{self};
+
+/// A URL from one of the mailing list headers.
+///
+/// The URL is stored in full, including the scheme.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnsubTarget {
+    /// A `mailto:` URL.
+    Mailto(String),
+    /// An `http:` or `https:` URL.
+    Http(String),
+    /// Any other URL.
+    Other(String),
+}
+
+impl UnsubTarget {
+    fn new(url: String) -> Self {
+        let scheme = url.split(':').next().unwrap_or("").to_ascii_lowercase();
+        match scheme.as_str() {
+            "mailto" => UnsubTarget::Mailto(url),
+            "http" | "https" => UnsubTarget::Http(url),
+            _ => UnsubTarget::Other(url),
+        }
+    }
+
+    /// The URL as a string.
+    pub fn url(&self) -> &str {
+        match self {
+            UnsubTarget::Mailto(u) | UnsubTarget::Http(u) | UnsubTarget::Other(u) => u,
+        }
+    }
+}
+
+impl Display for UnsubTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.url())
+    }
+}
+
+// Whitespace inside the angle brackets is ignored so that long URLs
+// can be folded.
+fn url(input: &[u8]) -> NomResult<'_, UnsubTarget> {
+    map(
+        delimited(tag("<"), take_while(|c| c != b'>'), tag(">")),
+        |u: &[u8]| {
+            let u: Vec<u8> = u
+                .iter()
+                .copied()
+                .filter(|c| !c.is_ascii_whitespace())
+                .collect();
+            UnsubTarget::new(String::from_utf8_lossy(&u).into_owned())
+        },
+    )(input)
+}
+
+fn url_list(input: &[u8]) -> NomResult<'_, Vec<UnsubTarget>> {
+    terminated(
+        separated_list1(
+            tag(","),
+            delimited(opt(cfws::<Intl>), url, opt(cfws::<Intl>)),
+        ),
+        opt(crlf),
+    )(input)
+}
+
+/// Parse the content of a `"List-Help:"` header.
+pub fn list_help(input: &[u8]) -> NomResult<'_, Vec<UnsubTarget>> {
+    url_list(input)
+}
+
+/// Parse the content of a `"List-Unsubscribe:"` header.
+pub fn list_unsubscribe(input: &[u8]) -> NomResult<'_, Vec<UnsubTarget>> {
+    url_list(input)
+}
+
+/// Parse the content of a `"List-Subscribe:"` header.
+pub fn list_subscribe(input: &[u8]) -> NomResult<'_, Vec<UnsubTarget>> {
+    url_list(input)
+}
+
+/// Parse the content of a `"List-Post:"` header.
+///
+/// Returns `None` for the special value `NO`, which indicates that
+/// posting to the list is not allowed.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2369::list_post;
+///
+/// let (_, targets) = list_post(b" <mailto:list@example.org>").unwrap();
+/// assert_eq!(targets.unwrap()[0].url(), "mailto:list@example.org");
+///
+/// let (_, targets) = list_post(b" NO (posting not allowed)").unwrap();
+/// assert_eq!(targets, None);
+/// ```
+pub fn list_post(input: &[u8]) -> NomResult<'_, Option<Vec<UnsubTarget>>> {
+    // "NO" must be followed by whitespace, a comment or the end.
+    let no = terminated(
+        tag_no_case("NO"),
+        not(take1_filter(|c| !c.is_ascii_whitespace() && c != b'(')),
+    );
+
+    alt((
+        map(
+            terminated(
+                delimited(opt(cfws::<Intl>), no, opt(cfws::<Intl>)),
+                opt(crlf),
+            ),
+            |_| None,
+        ),
+        map(url_list, Some),
+    ))(input)
+}
+
+/// Parse the content of a `"List-Owner:"` header.
+pub fn list_owner(input: &[u8]) -> NomResult<'_, Vec<UnsubTarget>> {
+    url_list(input)
+}
+
+/// Parse the content of a `"List-Archive:"` header.
+pub fn list_archive(input: &[u8]) -> NomResult<'_, Vec<UnsubTarget>> {
+    url_list(input)
+}
@@ -2,7 +2,9 @@
 //!
 //! [SMTP DSN]: https://tools.ietf.org/html/rfc3461
 
+use crate::headersection::unfold;
 use crate::rfc5322::atom;
+use crate::rfc5322::ofws;
 use crate::util::*;
 use charset::decode_ascii;
 use nom::branch::alt;
@@ -15,6 +17,8 @@ use nom::combinator::map_res;
 use nom::combinator::verify;
 use nom::multi::many0;
 use nom::multi::separated_list1;
+use nom::sequence::delimited;
+use nom::sequence::pair;
 use nom::sequence::preceded;
 use nom::sequence::separated_pair;
 use std::borrow::Cow;
@@ -58,11 +62,59 @@ fn _printable_xtext(input: &[u8]) -> NomResult<'_, Vec<u8>> {
 /// ```
 pub fn orcpt_address(input: &[u8]) -> NomResult<'_, (Cow<'_, str>, Cow<'_, str>)> {
     map(
-        separated_pair(atom::<crate::behaviour::Legacy>, tag(";"), _printable_xtext),
-        |(a, b)| (decode_ascii(a), Cow::Owned(decode_ascii(&b).into_owned())),
+        separated_pair(_address_type, tag(";"), _printable_xtext),
+        |(a, b)| (a, Cow::Owned(decode_ascii(&b).into_owned())),
     )(input)
 }
 
+fn _address_type(input: &[u8]) -> NomResult<'_, Cow<'_, str>> {
+    map(atom::<crate::behaviour::Legacy>, decode_ascii)(input)
+}
+
+/// Parse the `"Original-Recipient"` header of a delivery status or
+/// message disposition notification.
+///
+/// This is the header form of the ORCPT parameter described in [RFC
+/// 3464] and [RFC 3798]. Unlike [`orcpt_address`], the address is not
+/// xtext encoded and whitespace may surround the `";"`. The address
+/// must be printable ASCII, except for the `"utf-8"` address type of
+/// [RFC 6533] where it may be any UTF-8.
+///
+/// Returns the address type and the original recipient address.
+/// # Examples
+/// ```
+/// use rustyknife::rfc3461::original_recipient;
+///
+/// let (_, split) = original_recipient(b" rfc822; bob+tag@example.org\r\n").unwrap();
+/// assert_eq!(split, ("rfc822".into(), "bob+tag@example.org".into()));
+///
+/// let (_, split) = original_recipient(" utf-8;jöran@example.org".as_bytes()).unwrap();
+/// assert_eq!(split, ("utf-8".into(), "jöran@example.org".into()));
+/// ```
+///
+/// [RFC 3464]: https://tools.ietf.org/html/rfc3464#section-2.3.1
+/// [RFC 3798]: https://tools.ietf.org/html/rfc3798#section-3.2.3
+/// [RFC 6533]: https://tools.ietf.org/html/rfc6533#section-3
+pub fn original_recipient(input: &[u8]) -> NomResult<'_, (Cow<'_, str>, Cow<'_, str>)> {
+    let (rem, addr_type) = delimited(ofws, _address_type, pair(ofws, tag(";")))(input)?;
+    let address = unfold(rem);
+    let address = address.trim_ascii();
+
+    let address = if addr_type.eq_ignore_ascii_case("utf-8") {
+        str::from_utf8(address).map_err(|_| nom::Err::Error(()))?
+    } else if !address.is_empty() && address.iter().all(|c| matches!(c, 32..=126)) {
+        str::from_utf8(address).unwrap()
+    } else {
+        return Err(nom::Err::Error(()));
+    };
+
+    if address.is_empty() {
+        return Err(nom::Err::Error(()));
+    }
+
+    Ok((&rem[rem.len()..], (addr_type, Cow::Owned(address.into()))))
+}
+
 /// The DSN return type desired by the sender.
 #[derive(Debug, PartialEq)]
 pub enum DSNRet {
@@ -153,6 +205,7 @@ pub fn dsn_mail_params<'a>(
     ))
 }
 
+#[derive(Debug, PartialEq)]
 pub struct Notify {
     pub on_success: bool,
     pub on_failure: bool,
@@ -199,3 +252,82 @@ pub fn dsn_notify(input: &str) -> Result<(&str, Notify), nom::Err<()>> {
         map(separated_list1(tag(","), notify_item), convert_notify_list),
     ))(input)
 }
+
+/// DSN parameters for the RCPT command.
+#[derive(Debug, PartialEq)]
+pub struct DSNRcptParams {
+    /// The conditions under which a DSN should be sent.
+    ///
+    /// `None` if not specified.
+    pub notify: Option<Notify>,
+    /// The address type and decoded original recipient address.
+    ///
+    /// `None` if not specified.
+    pub orcpt: Option<(String, String)>,
+}
+
+/// Parse a list of ESMTP parameters on a RCPT TO command into a
+/// [`DSNRcptParams`] option block.
+///
+/// Returns the option block and a vector of parameters that were not
+/// consumed. As with ENVID in [`dsn_mail_params`], an ORCPT longer
+/// than the 500 characters allowed by RFC 3461 is rejected.
+/// # Examples
+/// ```
+/// use rustyknife::rfc3461::dsn_rcpt_params;
+/// let input = &[("ORCPT", Some("rfc822;bob+2B@example.org")),
+///               ("OTHER", None)];
+///
+/// let (params, other) = dsn_rcpt_params(input).unwrap();
+///
+/// assert_eq!(params.orcpt, Some(("rfc822".into(), "bob+@example.org".into())));
+/// assert_eq!(other, [("OTHER", None)]);
+/// ```
+pub fn dsn_rcpt_params<'a>(
+    input: &[Param<'a>],
+) -> Result<(DSNRcptParams, Vec<Param<'a>>), &'static str> {
+    let mut out = Vec::new();
+    let mut notify_val: Option<Notify> = None;
+    let mut orcpt_val: Option<(String, String)> = None;
+
+    for (name, value) in input {
+        match (name.to_lowercase().as_str(), value) {
+            ("notify", Some(value)) => {
+                if notify_val.is_some() {
+                    return Err("Duplicate NOTIFY");
+                }
+                if let Ok((_, parsed)) = all_consuming(dsn_notify)(value) {
+                    notify_val = Some(parsed);
+                } else {
+                    return Err("Invalid NOTIFY");
+                }
+            }
+
+            ("orcpt", Some(value)) => {
+                if orcpt_val.is_some() {
+                    return Err("Duplicate ORCPT");
+                }
+                let value = value.as_bytes();
+                if value.len() > 500 {
+                    return Err("ORCPT over 500 bytes");
+                }
+                if let Ok((_, (addr_type, addr))) = all_consuming(orcpt_address)(value) {
+                    orcpt_val = Some((addr_type.into(), addr.into()));
+                } else {
+                    return Err("Invalid ORCPT");
+                }
+            }
+            ("notify", None) => return Err("NOTIFY without value"),
+            ("orcpt", None) => return Err("ORCPT without value"),
+            _ => out.push((*name, *value)),
+        }
+    }
+
+    Ok((
+        DSNRcptParams {
+            notify: notify_val,
+            orcpt: orcpt_val,
+        },
+        out,
+    ))
+}
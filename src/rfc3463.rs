@@ -0,0 +1,85 @@
+//! [Enhanced mail system status codes]
+//!
+//! [Enhanced mail system status codes]: https://tools.ietf.org/html/rfc3463
+
+use crate::util::*;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::take_while_m_n;
+use nom::combinator::map;
+use nom::combinator::map_opt;
+use nom::sequence::preceded;
+use nom::sequence::tuple;
+use std::fmt::Display;
+use std::fmt::{self};
+
+/// An enhanced status code such as `5.1.1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EnhancedStatus {
+    /// The class: 2 for success, 4 for a transient failure or 5 for a
+    /// permanent failure.
+    pub class: u8,
+    /// The subject, from 0 to 999.
+    pub subject: u16,
+    /// The detail, from 0 to 999.
+    pub detail: u16,
+}
+
+impl EnhancedStatus {
+    /// Whether this status reports success.
+    pub fn is_success(&self) -> bool {
+        self.class == 2
+    }
+
+    /// Whether this status reports a failure that may go away if the
+    /// action is retried.
+    pub fn is_transient(&self) -> bool {
+        self.class == 4
+    }
+
+    /// Whether this status reports a failure that will not go away
+    /// without some change.
+    pub fn is_permanent(&self) -> bool {
+        self.class == 5
+    }
+}
+
+impl Display for EnhancedStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.class, self.subject, self.detail)
+    }
+}
+
+fn _number(input: &[u8]) -> NomResult<'_, u16> {
+    map_opt(take_while_m_n(1, 3, |c: u8| c.is_ascii_digit()), |n| {
+        parse_u64_bounded(n, 999).map(|n| n as u16)
+    })(input)
+}
+
+/// Parse an enhanced status code of the form `class.subject.detail`.
+///
+/// The class must be 2, 4 or 5 and the subject and detail are one to
+/// three digits.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc3463::enhanced_status;
+///
+/// let (_, status) = enhanced_status(b"4.7.1").unwrap();
+/// assert!(status.is_transient());
+/// assert_eq!(status.to_string(), "4.7.1");
+/// assert!(enhanced_status(b"3.0.0").is_err());
+/// ```
+pub fn enhanced_status(input: &[u8]) -> NomResult<'_, EnhancedStatus> {
+    map(
+        tuple((
+            take1_filter(|c| matches!(c, b'2' | b'4' | b'5')),
+            preceded(tag("."), _number),
+            preceded(tag("."), _number),
+        )),
+        |(class, subject, detail)| EnhancedStatus {
+            class: class - b'0',
+            subject,
+            detail,
+        },
+    )(input)
+}
@@ -0,0 +1,112 @@
+//! [Format=Flowed] text/plain
+//!
+//! [Format=Flowed]: https://tools.ietf.org/html/rfc3676
+
+/// The RFC 3676 parameters of a `"text/plain"` content type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlowedParams {
+    /// `format=flowed` is present.
+    pub flowed: bool,
+    /// `delsp=yes` is present.
+    pub delsp: bool,
+}
+
+/// Extract the [`FlowedParams`] from `"Content-Type"` parameters.
+///
+/// `params` is typically the output of [`content_type`]. Absent
+/// parameters default to `false`.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::content_type;
+/// use rustyknife::rfc3676::flowed_params;
+///
+/// let (_, (_, params)) = content_type(b"text/plain; format=flowed; delsp=yes").unwrap();
+/// let flowed = flowed_params(&params);
+/// assert!(flowed.flowed);
+/// assert!(flowed.delsp);
+/// ```
+///
+/// [`content_type`]: crate::rfc2231::content_type
+pub fn flowed_params(params: &[(String, String)]) -> FlowedParams {
+    let mut out = FlowedParams::default();
+
+    for (name, value) in params {
+        if name.eq_ignore_ascii_case("format") {
+            out.flowed = value.eq_ignore_ascii_case("flowed");
+        } else if name.eq_ignore_ascii_case("delsp") {
+            out.delsp = value.eq_ignore_ascii_case("yes");
+        }
+    }
+
+    out
+}
+
+/// Reflow a `format=flowed` body into logical lines.
+///
+/// Lines ending in a space are joined with the following line of the
+/// same quote depth, with the trailing space removed when `delsp` is
+/// set. Space-stuffing is undone and the signature separator `"-- "`
+/// is never treated as flowed. Quoted lines are output with their
+/// quote depth as a `">"` prefix followed by a space.
+///
+/// Output lines are terminated with `"\n"`.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc3676::unflow;
+///
+/// let body = "Hello \r\nworld.\r\n From here\r\n";
+/// assert_eq!(unflow(body, false), "Hello world.\nFrom here\n");
+/// ```
+pub fn unflow(body: &str, delsp: bool) -> String {
+    let mut out = String::with_capacity(body.len());
+    // Quote depth and contents of the logical line being built.
+    let mut current: Option<(usize, String)> = None;
+
+    for line in body.lines() {
+        let depth = line.bytes().take_while(|&c| c == b'>').count();
+        let mut text = &line[depth..];
+        text = text.strip_prefix(' ').unwrap_or(text);
+
+        let flowed = text.ends_with(' ') && text != "-- ";
+        if flowed && delsp {
+            text = &text[..text.len() - 1];
+        }
+
+        match &mut current {
+            Some((cur_depth, buf)) if *cur_depth == depth => buf.push_str(text),
+            _ => {
+                if let Some((cur_depth, buf)) = current.take() {
+                    push_line(&mut out, cur_depth, &buf);
+                }
+                current = Some((depth, text.into()));
+            }
+        }
+
+        if !flowed {
+            if let Some((cur_depth, buf)) = current.take() {
+                push_line(&mut out, cur_depth, &buf);
+            }
+        }
+    }
+
+    if let Some((cur_depth, buf)) = current {
+        push_line(&mut out, cur_depth, &buf);
+    }
+
+    out
+}
+
+fn push_line(out: &mut String, depth: usize, text: &str) {
+    if depth > 0 {
+        for _ in 0..depth {
+            out.push('>');
+        }
+        if !text.is_empty() {
+            out.push(' ');
+        }
+    }
+    out.push_str(text);
+    out.push('\n');
+}
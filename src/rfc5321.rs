@@ -4,6 +4,7 @@
 
 use crate::behaviour::Intl;
 use crate::behaviour::Legacy;
+use crate::rfc3463::enhanced_status;
 use crate::rfc5234::crlf;
 use crate::rfc5234::wsp;
 use crate::rfc5322::utf8_non_ascii;
@@ -12,12 +13,15 @@ use crate::util::*;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::bytes::complete::tag_no_case;
+use nom::bytes::complete::take_while;
 use nom::bytes::complete::take_while1;
 use nom::bytes::complete::take_while_m_n;
 use nom::combinator::all_consuming;
 use nom::combinator::map;
+use nom::combinator::map_opt;
 use nom::combinator::map_res;
 use nom::combinator::opt;
+use nom::combinator::peek;
 use nom::combinator::recognize;
 use nom::combinator::verify;
 use nom::error::ParseError;
@@ -30,10 +34,12 @@ use nom::sequence::pair;
 use nom::sequence::preceded;
 use nom::sequence::separated_pair;
 use nom::sequence::terminated;
+use nom::sequence::tuple;
 #[cfg(feature = "serde")]
 use serde::Deserialize;
 #[cfg(feature = "serde")]
 use serde::Serialize;
+use std::borrow::Cow;
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::fmt::{self};
@@ -387,15 +393,15 @@ pub(crate) fn local_part<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, LocalPart
 }
 
 fn _ip_int(input: &[u8]) -> NomResult<'_, u8> {
-    map_res(take_while_m_n(1, 3, |c: u8| c.is_ascii_digit()), |ip| {
-        str::from_utf8(ip).unwrap().parse()
+    map_opt(take_while_m_n(1, 3, |c: u8| c.is_ascii_digit()), |ip| {
+        parse_u64_bounded(ip, u8::MAX.into()).map(|n| n as u8)
     })(input)
 }
 
 fn _ipv4_literal(input: &[u8]) -> NomResult<'_, AddressLiteral> {
     map(
         pair(_ip_int, many_m_n(3, 3, preceded(tag("."), _ip_int))),
-        |(a, b)| (AddressLiteral::IP(Ipv4Addr::new(a, b[0], b[1], b[2]).into())),
+        |(a, b)| AddressLiteral::IP(Ipv4Addr::new(a, b[0], b[1], b[2]).into()),
     )(input)
 }
 
@@ -442,6 +448,22 @@ pub(crate) fn _domain_part<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DomainP
     ))(input)
 }
 
+/// Parse an address using the strict SMTP `"Mailbox"` grammar.
+///
+/// Unlike the Internet Message Format `"addr-spec"` used by
+/// [`Mailbox::from_imf`], comments and folding whitespace are not
+/// allowed. This is the grammar used by [`Mailbox::from_smtp`] and by
+/// the path parsers of this module.
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5321::mailbox;
+/// use rustyknife::types::Mailbox;
+///
+/// assert!(mailbox::<Intl>(b"user@example.org").is_ok());
+/// assert!(Mailbox::from_smtp(b"user@example.org (comment)").is_err());
+/// assert!(Mailbox::from_imf(b"user@example.org (comment)").is_ok());
+/// ```
 pub fn mailbox<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Mailbox> {
     map(
         separated_pair(local_part::<P>, tag("@"), _domain_part::<P>),
@@ -585,6 +607,40 @@ pub fn expn_command<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, SMTPString> {
     delimited(tag_no_case("EXPN "), _smtp_string::<P>, crlf)(input)
 }
 
+/// The argument of a `"VRFY"` or `"EXPN"` command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VrfyArg {
+    /// A complete mailbox, with or without angle brackets.
+    Mailbox(Mailbox),
+    /// Anything else, usually a user or list name.
+    String(SMTPString),
+}
+
+/// Parse the argument of a `"VRFY"` or `"EXPN"` command.
+///
+/// A mailbox is recognized with or without angle brackets. Otherwise
+/// the argument is parsed as an atom or quoted string.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5321::{vrfy_arg, VrfyArg};
+///
+/// let (_, arg) = vrfy_arg::<Intl>(b"<jqp@example.org>").unwrap();
+/// assert!(matches!(arg, VrfyArg::Mailbox(_)));
+/// let (_, arg) = vrfy_arg::<Intl>(b"\"John Q. Public\"").unwrap();
+/// assert!(matches!(arg, VrfyArg::String(s) if &*s == "John Q. Public"));
+/// ```
+pub fn vrfy_arg<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, VrfyArg> {
+    alt((
+        map(
+            alt((delimited(tag("<"), mailbox::<P>, tag(">")), mailbox::<P>)),
+            VrfyArg::Mailbox,
+        ),
+        map(_smtp_string::<P>, VrfyArg::String),
+    ))(input)
+}
+
 /// Parse an SMTP HELP command.
 pub fn help_command<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Option<SMTPString>> {
     delimited(
@@ -631,6 +687,23 @@ pub fn command<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Command> {
     ))(input)
 }
 
+/// Parse a mailbox, rejecting it if it exceeds the SMTP length limits.
+///
+/// Behaves like [`mailbox`] followed by [`Mailbox::validate`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5321::mailbox_with_limits;
+///
+/// assert!(mailbox_with_limits::<Intl>(b"bob@example.org").is_ok());
+/// let long = format!("{}@example.org", "a".repeat(300));
+/// assert!(mailbox_with_limits::<Intl>(long.as_bytes()).is_err());
+/// ```
+pub fn mailbox_with_limits<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Mailbox> {
+    verify(mailbox::<P>, |m: &Mailbox| m.validate().is_ok())(input)
+}
+
 /// Validates an email address.
 ///
 /// Does not accept the empty address.
@@ -647,6 +720,39 @@ pub fn validate_address<P: Utf8Policy>(i: &[u8]) -> bool {
     all_consuming(mailbox::<P>)(i).is_ok()
 }
 
+/// Return the normalized domain of a mailbox for routing.
+///
+/// The domain is lowercased and internationalized labels are converted
+/// to their ASCII form with [`Domain::to_ascii`], so equivalent domains
+/// compare equal. Returns `None` for address literals, which are routed
+/// by address instead, and for domains that cannot be converted to
+/// ASCII. The domain is borrowed if it is already normalized.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5321::recipient_domain;
+/// use rustyknife::types::Mailbox;
+///
+/// let mailbox = Mailbox::from_smtp("user@Bücher.Example".as_bytes()).unwrap();
+/// assert_eq!(recipient_domain(&mailbox).unwrap(), "xn--bcher-kva.example");
+/// let mailbox = Mailbox::from_smtp(b"user@[192.0.2.1]").unwrap();
+/// assert_eq!(recipient_domain(&mailbox), None);
+/// ```
+pub fn recipient_domain(mailbox: &Mailbox) -> Option<Cow<'_, str>> {
+    let domain = match mailbox.domain_part() {
+        DomainPart::Domain(domain) => domain,
+        DomainPart::Address(_) => return None,
+    };
+
+    Some(if !domain.is_ascii() {
+        Cow::Owned(domain.to_ascii().ok()?.0)
+    } else if domain.bytes().any(|c| c.is_ascii_uppercase()) {
+        Cow::Owned(domain.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(domain)
+    })
+}
+
 /// Parse a STARTTLS command from RFC 3207
 pub fn starttls_command(input: &[u8]) -> NomResult<'_, ()> {
     map(tag_no_case("STARTTLS\r\n"), |_| ())(input)
@@ -664,7 +770,85 @@ pub fn bdat_command(input: &[u8]) -> NomResult<'_, (u64, bool)> {
 }
 
 fn bdat_chunk_size(input: &[u8]) -> NomResult<'_, u64> {
-    map_res(take_while_m_n(1, 20, |c: u8| c.is_ascii_digit()), |s| {
-        std::str::from_utf8(s).unwrap().parse()
+    map_opt(take_while_m_n(1, 20, |c: u8| c.is_ascii_digit()), |s| {
+        parse_u64_bounded(s, u64::MAX)
     })(input)
 }
+
+/// A single line of an SMTP reply.
+///
+/// A multiline reply is a sequence of lines where every line except
+/// the last has [`ReplyLine::more`] set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplyLine {
+    /// The three digit reply code, such as `250`.
+    pub code: u16,
+    /// Set if the code was followed by `"-"`, meaning that more lines
+    /// follow.
+    pub more: bool,
+    /// The [RFC 3463] enhanced status code as `(class, subject,
+    /// detail)`, if the text starts with one.
+    ///
+    /// [RFC 3463]: https://tools.ietf.org/html/rfc3463
+    pub enhanced: Option<(u8, u16, u16)>,
+    /// The remaining text, without the enhanced status code.
+    pub text: String,
+}
+
+fn reply_code(input: &[u8]) -> NomResult<'_, u16> {
+    map(
+        tuple((
+            take1_filter(|c| (b'2'..=b'5').contains(&c)),
+            take1_filter(|c| (b'0'..=b'5').contains(&c)),
+            take1_filter(|c| c.is_ascii_digit()),
+        )),
+        |(a, b, c)| u16::from(a - b'0') * 100 + u16::from(b - b'0') * 10 + u16::from(c - b'0'),
+    )(input)
+}
+
+/// Parse a single line of an SMTP reply such as `"250-2.1.0 OK\r\n"`.
+///
+/// The enhanced status code is only recognized if it is followed by a
+/// space or the end of the line. The text is not restricted to ASCII
+/// since some servers send UTF-8; invalid UTF-8 is replaced.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5321::reply_line;
+///
+/// let (_, line) = reply_line(b"250-2.1.0 Sender OK\r\n").unwrap();
+/// assert_eq!((line.code, line.more), (250, true));
+/// assert_eq!(line.enhanced, Some((2, 1, 0)));
+/// assert_eq!(line.text, "Sender OK");
+/// ```
+pub fn reply_line(input: &[u8]) -> NomResult<'_, ReplyLine> {
+    let text = |i| take_while(|c| c != b'\r' && c != b'\n')(i);
+    let enhanced = terminated(
+        map(enhanced_status, |s| (s.class, s.subject, s.detail)),
+        alt((tag(" "), peek(crlf))),
+    );
+
+    map(
+        terminated(
+            pair(
+                reply_code,
+                opt(pair(alt((tag("-"), tag(" "))), pair(opt(enhanced), text))),
+            ),
+            crlf,
+        ),
+        |(code, rest)| match rest {
+            Some((sep, (enhanced, text))) => ReplyLine {
+                code,
+                more: sep == b"-",
+                enhanced,
+                text: String::from_utf8_lossy(text).into(),
+            },
+            None => ReplyLine {
+                code,
+                more: false,
+                enhanced: None,
+                text: String::new(),
+            },
+        },
+    )(input)
+}
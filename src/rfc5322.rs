@@ -1,25 +1,44 @@
 //! Parsers for [Internet Message Format] messages.
 //!
-//! Comments are ignored. [RFC 2047] decoding is applied where appropriate.
+//! Comments are ignored. A comment between the words of a display name
+//! is treated as whitespace unless [`ElideComments`] is used. [RFC 2047]
+//! decoding is applied where appropriate.
 //!
 //! [Internet Message Format]: https://tools.ietf.org/html/rfc5322
 //! [RFC 2047]: https://tools.ietf.org/html/rfc2047
+//! [`ElideComments`]: crate::behaviour::ElideComments
 
 use crate::behaviour::*;
+use crate::headersection::find_header;
+use crate::headersection::unfold;
+use crate::headersection::HeaderField;
+use crate::rfc2047::encode_word;
 use crate::rfc2047::encoded_word;
+use crate::rfc2047::header_needs_encoding;
+use crate::rfc2047::is_unterminated_encoded_word;
+#[cfg(feature = "quoted-string-rfc2047")]
 use crate::rfc2047::EncodedWord;
+use crate::rfc2047::WordEncoding;
 use crate::rfc5234::*;
 use crate::types::*;
 use crate::types::{self};
 use crate::util::*;
+use encoding_rs::Encoding;
+use encoding_rs::UTF_8;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
+use nom::bytes::complete::tag_no_case;
 use nom::bytes::complete::take;
+use nom::bytes::complete::take_while1;
+use nom::bytes::complete::take_while_m_n;
+use nom::combinator::consumed;
 use nom::combinator::map;
 use nom::combinator::map_opt;
 use nom::combinator::opt;
 use nom::combinator::recognize;
+use nom::combinator::verify;
 use nom::multi::fold_many0;
+use nom::multi::fold_many1;
 use nom::multi::many0;
 use nom::multi::many1;
 use nom::multi::separated_list1;
@@ -28,12 +47,66 @@ use nom::sequence::pair;
 use nom::sequence::preceded;
 use nom::sequence::separated_pair;
 use nom::sequence::terminated;
+use nom::sequence::tuple;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use std::borrow::Cow;
+use std::fmt::Display;
+use std::fmt::{self};
 use std::mem;
+use std::ops::Range;
 use std::str;
 
+/// Options that vary the behaviour of the parsers.
+///
+/// A policy selects these through [`Behaviour`]. Wrappers such as
+/// [`ElideComments`] change them on top of the wrapped policy.
+#[derive(Clone, Copy, Debug)]
+pub struct ParseOptions {
+    /// Drop a comment between two words of a display name instead of
+    /// treating it as whitespace.
+    pub elide_comments: bool,
+    /// Accept common deviations from the standard. The parsers
+    /// document what they accept in this mode.
+    pub lenient: bool,
+    /// Reject groups in `"From:"` and `"Sender:"` as in RFC 5322,
+    /// instead of accepting them as allowed by RFC 6854.
+    pub no_group_senders: bool,
+    /// The maximum nesting depth of comments. Deeper comments are a
+    /// parse error, which protects against stack overflows on hostile
+    /// input.
+    pub max_comment_depth: usize,
+}
+
+impl ParseOptions {
+    /// The default for [`max_comment_depth`](Self::max_comment_depth).
+    pub const DEFAULT_MAX_COMMENT_DEPTH: usize = 100;
+
+    /// The options of [`Legacy`] and [`Intl`].
+    pub const DEFAULT: ParseOptions = ParseOptions {
+        elide_comments: false,
+        lenient: false,
+        no_group_senders: false,
+        max_comment_depth: Self::DEFAULT_MAX_COMMENT_DEPTH,
+    };
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The [`ParseOptions`] of a policy.
+pub trait Behaviour {
+    /// The options the parsers use with this policy.
+    const OPTIONS: ParseOptions = ParseOptions::DEFAULT;
+}
+
 #[allow(missing_docs)] // Mostly internal
-pub trait Utf8Policy {
+pub trait Utf8Policy: Behaviour {
     fn vchar(input: &[u8]) -> NomResult<'_, char>;
     fn ctext(input: &[u8]) -> NomResult<'_, char>;
     fn atext(input: &[u8]) -> NomResult<'_, char>;
@@ -41,6 +114,8 @@ pub trait Utf8Policy {
     fn dtext(input: &[u8]) -> NomResult<'_, char>;
 }
 
+impl Behaviour for Legacy {}
+
 impl Utf8Policy for Legacy {
     fn vchar(input: &[u8]) -> NomResult<'_, char> {
         crate::rfc5234::vchar(input)
@@ -78,6 +153,8 @@ impl Utf8Policy for Legacy {
     }
 }
 
+impl Behaviour for Intl {}
+
 impl Utf8Policy for Intl {
     fn vchar(input: &[u8]) -> NomResult<'_, char> {
         alt((Legacy::vchar, utf8_non_ascii))(input)
@@ -107,6 +184,46 @@ impl Utf8Policy for Intl {
     }
 }
 
+// Implement a policy that wraps another one, setting one of the
+// options on top of the wrapped policy's.
+macro_rules! wrapper_policy {
+    ($wrapper:ident $(<const $n:ident: usize>)?, $option:ident: $value:expr) => {
+        impl<P: Utf8Policy $(, const $n: usize)?> Utf8Policy for $wrapper<P $(, $n)?> {
+            fn vchar(input: &[u8]) -> NomResult<'_, char> {
+                P::vchar(input)
+            }
+
+            fn ctext(input: &[u8]) -> NomResult<'_, char> {
+                P::ctext(input)
+            }
+
+            fn atext(input: &[u8]) -> NomResult<'_, char> {
+                P::atext(input)
+            }
+
+            fn qtext(input: &[u8]) -> NomResult<'_, char> {
+                P::qtext(input)
+            }
+
+            fn dtext(input: &[u8]) -> NomResult<'_, char> {
+                P::dtext(input)
+            }
+        }
+
+        impl<P: Utf8Policy $(, const $n: usize)?> Behaviour for $wrapper<P $(, $n)?> {
+            const OPTIONS: ParseOptions = ParseOptions {
+                $option: $value,
+                ..P::OPTIONS
+            };
+        }
+    };
+}
+
+wrapper_policy!(ElideComments, elide_comments: true);
+wrapper_policy!(Lenient, lenient: true);
+wrapper_policy!(NoGroupSenders, no_group_senders: true);
+wrapper_policy!(CommentDepth<const N: usize>, max_comment_depth: N);
+
 fn quoted_pair<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, char> {
     preceded(tag("\\"), alt((P::vchar, map(wsp, char::from))))(input)
 }
@@ -114,19 +231,26 @@ fn quoted_pair<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, char> {
 #[derive(Clone, Debug)]
 enum CommentContent<'a> {
     Text(Cow<'a, str>),
+    EncodedWord(String),
     Comment(Vec<CommentContent<'a>>),
     QP(char),
 }
 
-fn ccontent<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, CommentContent<'_>> {
+fn ccontent<P: Utf8Policy>(input: &[u8], depth: usize) -> NomResult<'_, CommentContent<'_>> {
     alt((
         alt((
+            map(encoded_word, |w| {
+                CommentContent::EncodedWord(w.decode().into())
+            }),
             map(recognize_many1(P::ctext), |ct| {
                 CommentContent::Text(str::from_utf8(ct).unwrap().into())
             }),
             map(quoted_pair::<P>, CommentContent::QP),
         )),
-        map(comment::<P>, CommentContent::Comment),
+        map(
+            |i| _nested_comment::<P>(i, depth + 1),
+            CommentContent::Comment,
+        ),
     ))(input)
 }
 
@@ -181,12 +305,23 @@ fn _concat_comment<'a, I: IntoIterator<Item = CommentContent<'a>>>(
 }
 
 fn comment<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<CommentContent<'_>>> {
+    _nested_comment::<P>(input, 1)
+}
+
+fn _nested_comment<P: Utf8Policy>(
+    input: &[u8],
+    depth: usize,
+) -> NomResult<'_, Vec<CommentContent<'_>>> {
+    if depth > P::OPTIONS.max_comment_depth {
+        return Err(nom::Err::Error(()));
+    }
+
     map(
         delimited(
             tag("("),
             pair(
                 fold_many0(
-                    pair(ofws, ccontent::<P>),
+                    pair(ofws, |i| ccontent::<P>(i, depth)),
                     Vec::new(),
                     |mut acc, (fws, cc)| {
                         acc.push(CommentContent::Text(fws));
@@ -207,13 +342,18 @@ fn comment<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<CommentContent<'_>>
     )(input)
 }
 
-fn cfws<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, &[u8]> {
+// CFWS, also returning the content of its comments.
+fn _cfws_comments<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Vec<CommentContent<'_>>>> {
     alt((
-        recognize(pair(many1(pair(ofws, comment::<P>)), ofws)),
-        recognize(fws),
+        map(pair(many1(preceded(ofws, comment::<P>)), ofws), |(c, _)| c),
+        map(fws, |_| Vec::new()),
     ))(input)
 }
 
+pub(crate) fn cfws<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, &[u8]> {
+    recognize(_cfws_comments::<P>)(input)
+}
+
 #[cfg(feature = "quoted-string-rfc2047")]
 fn qcontent<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, QContent<'_>> {
     alt((
@@ -226,7 +366,7 @@ fn qcontent<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, QContent<'_>> {
 }
 
 #[cfg(not(feature = "quoted-string-rfc2047"))]
-fn qcontent<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, QContent> {
+fn qcontent<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, QContent<'_>> {
     alt((
         map(recognize_many1(P::qtext), |q| {
             QContent::Literal(String::from_utf8_lossy(q))
@@ -272,7 +412,21 @@ pub(crate) fn quoted_string<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Quoted
 }
 
 /// A single mailbox with an optional display name.
+///
+/// Parsing from a string uses the [`Intl`] policy and rejects trailing
+/// input.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5322::Mailbox;
+///
+/// let mailbox: Mailbox = "John Doe <j@example.org>".parse().unwrap();
+/// assert_eq!(mailbox.dname.as_deref(), Some("John Doe"));
+/// assert!("John Doe <j@example.org> junk".parse::<Mailbox>().is_err());
+/// ```
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub struct Mailbox {
     /// The display name.
     pub dname: Option<String>,
@@ -280,8 +434,100 @@ pub struct Mailbox {
     pub address: types::Mailbox,
 }
 
+impl Mailbox {
+    /// Return the display name with whitespace normalized.
+    ///
+    /// Runs of whitespace left over from folding are collapsed to a
+    /// single space and leading and trailing whitespace is removed,
+    /// which is how mail clients render names. Returns `None` if
+    /// there is no display name or if it is empty once trimmed.
+    /// # Examples
+    /// ```
+    /// use rustyknife::behaviour::Intl;
+    /// use rustyknife::rfc5322::{from, Address};
+    ///
+    /// let (_, parsed) = from::<Intl>(b"\" John \t Doe \" <jdoe@example.org>").unwrap();
+    /// match &parsed[0] {
+    ///     Address::Mailbox(m) => assert_eq!(m.normalized_dname(), Some("John Doe".into())),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn normalized_dname(&self) -> Option<String> {
+        let dname = self.dname.as_ref()?;
+        let normalized = dname.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if normalized.is_empty() {
+            None
+        } else {
+            Some(normalized)
+        }
+    }
+}
+
+/// Quote a phrase such as a display name if needed.
+///
+/// Returns the input unchanged if it is a sequence of atoms separated
+/// by single spaces. Otherwise it is returned as a quoted string with
+/// `"` and `\\` escaped, so that it parses back to the same text.
+///
+/// Text that [`header_needs_encoding`], such as non-ASCII text or
+/// control characters, is RFC 2047 encoded instead.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5322::quote_phrase;
+///
+/// assert_eq!(quote_phrase("John Smith"), "John Smith");
+/// assert_eq!(quote_phrase("Smith, John"), "\"Smith, John\"");
+/// assert_eq!(quote_phrase("a \"b\""), r#""a \"b\"""#);
+/// assert_eq!(quote_phrase("a\r\nb"), "=?utf-8?b?YQ0KYg==?=");
+/// ```
+///
+/// [`header_needs_encoding`]: crate::rfc2047::header_needs_encoding
+pub fn quote_phrase(phrase: &str) -> Cow<'_, str> {
+    let is_atext = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c);
+
+    if header_needs_encoding(phrase) {
+        Cow::Owned(encode_word(phrase, UTF_8, WordEncoding::B))
+    } else if phrase
+        .split(' ')
+        .all(|w| !w.is_empty() && w.chars().all(is_atext))
+    {
+        Cow::Borrowed(phrase)
+    } else {
+        Cow::Owned(QuotedString(phrase.into()).quoted())
+    }
+}
+
+// Write a display name so that it parses back to the same string.
+fn write_phrase(f: &mut fmt::Formatter<'_>, phrase: &str) -> fmt::Result {
+    f.write_str(&quote_phrase(phrase))
+}
+
+/// Formats as `dname <address>`, or just the address when there is no
+/// display name.
+impl Display for Mailbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.dname {
+            Some(dname) => {
+                write_phrase(f, dname)?;
+                write!(f, " <{}>", self.address)
+            }
+            None => write!(f, "{}", self.address),
+        }
+    }
+}
+
+impl From<Mailbox> for String {
+    fn from(value: Mailbox) -> String {
+        value.to_string()
+    }
+}
+
 /// A group of many [`Mailbox`].
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub struct Group {
     /// This group's display name.
     pub dname: String,
@@ -289,8 +535,50 @@ pub struct Group {
     pub members: Vec<Mailbox>,
 }
 
+/// Formats as `dname: member1, member2;`, or `dname:;` for an empty
+/// group.
+///
+/// The display names are quoted or RFC 2047 encoded as needed so that
+/// the output parses back to an equal group.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5322::{Group, Mailbox};
+///
+/// let group = Group {
+///     dname: "Équipe".into(),
+///     members: vec![Mailbox {
+///         dname: Some("John Q. Public".into()),
+///         address: "jqp@example.org".parse().unwrap(),
+///     }],
+/// };
+/// assert_eq!(
+///     group.to_string(),
+///     "=?utf-8?b?w4lxdWlwZQ==?=: \"John Q. Public\" <jqp@example.org>;"
+/// );
+/// ```
+impl Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_phrase(f, &self.dname)?;
+        f.write_str(":")?;
+        for (i, member) in self.members.iter().enumerate() {
+            f.write_str(if i == 0 { " " } else { ", " })?;
+            write!(f, "{}", member)?;
+        }
+        f.write_str(";")
+    }
+}
+
+impl From<Group> for String {
+    fn from(value: Group) -> String {
+        value.to_string()
+    }
+}
+
 /// An address is either a single [`Mailbox`] or a [`Group`].
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub enum Address {
     /// Single [`Mailbox`].
     Mailbox(Mailbox),
@@ -298,6 +586,71 @@ pub enum Address {
     Group(Group),
 }
 
+/// Formats as the contained [`Mailbox`] or [`Group`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::from;
+///
+/// let input = "\"Smith, John\" <jsmith@example.org>";
+/// let (_, parsed) = from::<Intl>(input.as_bytes()).unwrap();
+/// assert_eq!(parsed[0].to_string(), input);
+/// ```
+impl Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Mailbox(mailbox) => write!(f, "{}", mailbox),
+            Address::Group(group) => write!(f, "{}", group),
+        }
+    }
+}
+
+impl From<Address> for String {
+    fn from(value: Address) -> String {
+        value.to_string()
+    }
+}
+
+/// List every mailbox in `addrs`, including the members of groups.
+///
+/// Groups contribute their members in order and empty groups
+/// contribute nothing. See [`flatten_addresses_owned`] to take
+/// ownership of the mailboxes.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::{flatten_addresses, to};
+///
+/// let (_, addrs) = to::<Intl>(b"Team: a@example.org, b@example.org;, c@example.org").unwrap();
+/// let mailboxes: Vec<_> = flatten_addresses(&addrs)
+///     .iter()
+///     .map(|m| m.address.to_string())
+///     .collect();
+/// assert_eq!(mailboxes, ["a@example.org", "b@example.org", "c@example.org"]);
+/// ```
+pub fn flatten_addresses(addrs: &[Address]) -> Vec<&Mailbox> {
+    addrs
+        .iter()
+        .flat_map(|a| match a {
+            Address::Mailbox(m) => std::slice::from_ref(m),
+            Address::Group(g) => &g.members[..],
+        })
+        .collect()
+}
+
+/// Like [`flatten_addresses`] but consumes the list.
+pub fn flatten_addresses_owned(addrs: Vec<Address>) -> Vec<Mailbox> {
+    addrs
+        .into_iter()
+        .flat_map(|a| match a {
+            Address::Mailbox(m) => vec![m],
+            Address::Group(g) => g.members,
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 enum QContent<'a> {
     Literal(Cow<'a, str>),
@@ -353,68 +706,137 @@ pub(crate) fn utf8_non_ascii(input: &[u8]) -> NomResult<'_, char> {
     alt((_single_char(4), _single_char(3), _single_char(2)))(input)
 }
 
-pub(crate) fn dot_atom<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DotAtom> {
+// dot-atom not surrounded by CFWS
+fn _dot_atom_text<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DotAtom> {
     map(
-        delimited(
-            opt(cfws::<P>),
-            recognize(pair(
-                recognize_many1(P::atext),
-                recognize_many0(pair(tag("."), recognize_many1(P::atext))),
-            )),
-            opt(cfws::<P>),
-        ),
-        |a| (DotAtom(str::from_utf8(a).unwrap().into())),
+        recognize(pair(
+            recognize_many1(P::atext),
+            recognize_many0(pair(tag("."), recognize_many1(P::atext))),
+        )),
+        |a| DotAtom(str::from_utf8(a).unwrap().into()),
     )(input)
 }
 
+pub(crate) fn dot_atom<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DotAtom> {
+    delimited(opt(cfws::<P>), _dot_atom_text::<P>, opt(cfws::<P>))(input)
+}
+
 pub(crate) fn atom<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, &[u8]> {
     delimited(opt(cfws::<P>), recognize_many1(P::atext), opt(cfws::<P>))(input)
 }
 
-pub(crate) fn _padded_encoded_word<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, EncodedWord<'_>> {
-    delimited(opt(cfws::<P>), encoded_word, opt(cfws::<P>))(input)
+/// Whitespace and comments surrounding a word.
+///
+/// Returns the content of the comments.
+fn _word_cfws<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Vec<CommentContent<'_>>>> {
+    fold_many1(
+        alt((map(fws, |_| None), map(comment::<P>, Some))),
+        Vec::new(),
+        |mut acc, c| {
+            acc.extend(c);
+            acc
+        },
+    )(input)
 }
 
-fn word<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Text<'_>> {
-    alt((
-        map(_padded_encoded_word::<P>, |w| {
-            Text::Literal(w.decode().into_owned())
-        }),
-        map(atom::<P>, |x| Text::Atom(str::from_utf8(x).unwrap())),
-        map(quoted_string::<P>, |qs| Text::Literal(qs.0)),
-    ))(input)
+struct Word<'a> {
+    text: Text<'a>,
+    comments_before: Vec<Vec<CommentContent<'a>>>,
+    comments_after: Vec<Vec<CommentContent<'a>>>,
+    encoded: bool,
 }
 
-fn _concat_atom_and_qs<'a, A>(input: A) -> String
-where
-    A: Iterator<Item = Text<'a>>,
-{
-    let mut iter = input.peekable();
-    let mut out = String::new();
+#[cfg(feature = "quoted-string-rfc2047")]
+fn _has_encoded_word(qc: &[QContent<'_>]) -> bool {
+    qc.iter().any(|c| matches!(c, QContent::EncodedWord(_)))
+}
 
-    while let Some(cur) = iter.next() {
-        match (cur, iter.peek()) {
-            (Text::Atom(v), Some(_)) => {
-                out.push_str(v);
-                out.push(' ')
-            }
-            (_, Some(Text::Atom(v))) => {
-                out.push_str(v);
-                out.push(' ')
+#[cfg(not(feature = "quoted-string-rfc2047"))]
+fn _has_encoded_word(_: &[QContent<'_>]) -> bool {
+    false
+}
+
+fn word<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Word<'_>> {
+    map(
+        tuple((
+            opt(_word_cfws::<P>),
+            alt((
+                map(encoded_word, |w| {
+                    (Text::Literal(w.decode().into_owned()), true)
+                }),
+                map(recognize_many1(P::atext), |x| {
+                    (Text::Atom(str::from_utf8(x).unwrap()), false)
+                }),
+                map(_inner_quoted_string::<P>, |qc| {
+                    let encoded = _has_encoded_word(&qc);
+                    (Text::Literal(concat_qs(qc.into_iter())), encoded)
+                }),
+            )),
+            opt(_word_cfws::<P>),
+        )),
+        |(before, (text, encoded), after)| Word {
+            text,
+            comments_before: before.unwrap_or_default(),
+            comments_after: after.unwrap_or_default(),
+            encoded,
+        },
+    )(input)
+}
+
+/// Atoms are separated by a single space while quoted strings and
+/// encoded words are joined as is. A comment between two words is
+/// semantically whitespace and also separates them by a space,
+/// unless `elide_comments` is set.
+fn _concat_words<P: Utf8Policy>(words: &[Word<'_>]) -> String {
+    let mut out = String::new();
+    let mut prev: Option<&Word<'_>> = None;
+
+    for cur in words {
+        if let Some(prev) = prev {
+            let comment = !prev.comments_after.is_empty() || !cur.comments_before.is_empty();
+            let atom = matches!(prev.text, Text::Atom(_)) || matches!(cur.text, Text::Atom(_));
+            if atom || (comment && !P::OPTIONS.elide_comments) {
+                out.push(' ');
             }
-            (ref t1, _) => out.push_str(t1.into()),
-        };
+        }
+        out.push_str((&cur.text).into());
+        prev = Some(cur);
     }
 
     out
 }
 
 fn display_name<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
+    map(_display_name_flagged::<P>, |(dname, _)| dname)(input)
+}
+
+// Also returns whether any word was RFC 2047 encoded.
+fn _display_name_flagged<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, (String, bool)> {
     map(many1(word::<P>), |words| {
-        _concat_atom_and_qs(words.into_iter())
+        (_concat_words::<P>(&words), words.iter().any(|w| w.encoded))
     })(input)
 }
 
+/// Parse a standalone display name.
+///
+/// This is the phrase portion of a mailbox such as `"John Doe"` in
+/// `"John Doe <jdoe@example.org>"`, for callers that have already
+/// isolated it. RFC 2047 encoded words are decoded and the words are
+/// concatenated.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::display_name_only;
+///
+/// let (rem, name) = display_name_only::<Intl>(b"John =?utf-8?q?Pu=C3=A9blic?=").unwrap();
+/// assert_eq!(rem, b"");
+/// assert_eq!(name, "John Puéblic");
+/// ```
+pub fn display_name_only<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, String> {
+    display_name::<P>(i)
+}
+
 pub(crate) fn local_part<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, LocalPart> {
     alt((
         map(dot_atom::<P>, |a| a.into()),
@@ -422,12 +844,13 @@ pub(crate) fn local_part<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, LocalPart
     ))(input)
 }
 
-pub(crate) fn domain_literal<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, AddressLiteral> {
+// domain-literal not surrounded by CFWS
+fn _domain_literal_text<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, AddressLiteral> {
     map(
         delimited(
-            pair(opt(cfws::<P>), tag("[")),
+            tag("["),
             pair(many0(pair(ofws, recognize_many1(P::dtext))), ofws),
-            pair(tag("]"), opt(cfws::<P>)),
+            tag("]"),
         ),
         |(a, b)| {
             let mut out: String = a
@@ -441,6 +864,10 @@ pub(crate) fn domain_literal<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Addre
     )(input)
 }
 
+pub(crate) fn domain_literal<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, AddressLiteral> {
+    delimited(opt(cfws::<P>), _domain_literal_text::<P>, opt(cfws::<P>))(input)
+}
+
 pub(crate) fn _domain<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Domain> {
     map(dot_atom::<P>, |a| Domain(a.0))(input)
 }
@@ -452,17 +879,133 @@ pub(crate) fn domain<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DomainPart> {
     ))(input)
 }
 
-pub(crate) fn addr_spec<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, types::Mailbox> {
+/// Parse a bare address such as `"jdoe@example.org"`.
+///
+/// Returns a [`types::Mailbox`], which has no display name. Use
+/// [`mailbox`] to also accept the `"John Doe <jdoe@example.org>"` form.
+///
+/// Comments and folding whitespace are allowed around the local and
+/// domain parts as in RFC 5322. Use
+/// [`rfc5321::mailbox`](crate::rfc5321::mailbox) to validate an SMTP
+/// envelope address, which allows neither.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::addr_spec;
+///
+/// let (_, address) = addr_spec::<Intl>(b"jdoe (John) @example.org").unwrap();
+/// assert_eq!(address.to_string(), "jdoe@example.org");
+/// assert!(addr_spec::<Intl>(b"John Doe <jdoe@example.org>").is_err());
+/// ```
+pub fn addr_spec<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, types::Mailbox> {
     map(
         separated_pair(local_part::<P>, tag("@"), domain::<P>),
         |(lp, domain)| types::Mailbox(lp, domain),
     )(input)
 }
 
+/// Check whether `s` is a syntactically valid email address.
+///
+/// The whole string must be an [`addr_spec`] such as
+/// `"jdoe@example.org"` with only ASCII characters. Display names are
+/// not accepted. This validates syntax only and says nothing about
+/// whether mail can be delivered to the address.
+///
+/// Unlike in a header, comments and whitespace around the local and
+/// domain parts are rejected, as are line breaks.
+///
+/// Use [`is_valid_email_intl`] to also accept internationalized
+/// addresses.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5322::is_valid_email;
+///
+/// assert!(is_valid_email("jdoe@example.org"));
+/// assert!(!is_valid_email("John Doe <jdoe@example.org>"));
+/// assert!(!is_valid_email("jdoe@bücher.example"));
+/// assert!(!is_valid_email("jdoe@example.org (John Doe)"));
+/// ```
+pub fn is_valid_email(s: &str) -> bool {
+    _is_valid_email::<Legacy>(s)
+}
+
+/// Check whether `s` is a syntactically valid internationalized email
+/// address.
+///
+/// Like [`is_valid_email`] but UTF-8 is allowed as in [RFC 6532].
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5322::is_valid_email_intl;
+///
+/// assert!(is_valid_email_intl("jörg@bücher.example"));
+/// assert!(!is_valid_email_intl("jörg"));
+/// ```
+///
+/// [RFC 6532]: https://tools.ietf.org/html/rfc6532
+pub fn is_valid_email_intl(s: &str) -> bool {
+    _is_valid_email::<Intl>(s)
+}
+
+// addr-spec without CFWS around its parts
+fn _bare_addr_spec<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, types::Mailbox> {
+    map(
+        separated_pair(
+            alt((
+                map(_dot_atom_text::<P>, |a| a.into()),
+                map(_inner_quoted_string::<P>, |qc| {
+                    LocalPart::Quoted(QuotedString(concat_qs(qc.into_iter())))
+                }),
+            )),
+            tag("@"),
+            alt((
+                map(_dot_atom_text::<P>, |a| DomainPart::Domain(Domain(a.0))),
+                map(_domain_literal_text::<P>, DomainPart::Address),
+            )),
+        ),
+        |(lp, domain)| types::Mailbox(lp, domain),
+    )(input)
+}
+
+fn _is_valid_email<P: Utf8Policy>(s: &str) -> bool {
+    !s.contains(['\r', '\n']) && nom::exact!(s.as_bytes(), _bare_addr_spec::<P>).is_ok()
+}
+
+// The obsolete source route such as "@a.example,@b.example:" that
+// may precede the address in an angle-addr. The domains are
+// discarded.
+fn _obs_route<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, ()> {
+    map(
+        tuple((
+            many0(alt((cfws::<P>, tag(",")))),
+            tag("@"),
+            domain::<P>,
+            many0(tuple((
+                tag(","),
+                opt(cfws::<P>),
+                opt(preceded(tag("@"), domain::<P>)),
+            ))),
+            tag(":"),
+        )),
+        |_| (),
+    )(input)
+}
+
+// With a lenient policy, an obsolete source route is skipped.
+fn _angle_addr_route<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, ()> {
+    if P::OPTIONS.lenient {
+        map(opt(_obs_route::<P>), |_| ())(input)
+    } else {
+        Ok((input, ()))
+    }
+}
+
 fn angle_addr<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, types::Mailbox> {
     delimited(
         pair(opt(cfws::<P>), tag("<")),
-        addr_spec::<P>,
+        preceded(_angle_addr_route::<P>, addr_spec::<P>),
         pair(tag(">"), opt(cfws::<P>)),
     )(input)
 }
@@ -474,64 +1017,483 @@ fn name_addr<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Mailbox> {
     )(input)
 }
 
-fn mailbox<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Mailbox> {
+/// Parse a mailbox in the `dname <address>` form, flagging encoded
+/// display names.
+///
+/// The flag is `true` if the display name contained RFC 2047 encoded
+/// words, so that the decoded name differs from the raw header text.
+/// Encoded words are sometimes used to hide non-ASCII look-alike
+/// characters from filters, which security UIs may want to warn about.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::name_addr_flagged;
+///
+/// let (_, (mailbox, encoded)) =
+///     name_addr_flagged::<Intl>(b"=?utf-8?q?P=D0=B0yPal?= <evil@example.org>").unwrap();
+/// assert_eq!(mailbox.dname.unwrap(), "P\u{430}yPal");
+/// assert!(encoded);
+///
+/// let (_, (_, encoded)) = name_addr_flagged::<Intl>(b"PayPal <evil@example.org>").unwrap();
+/// assert!(!encoded);
+/// ```
+pub fn name_addr_flagged<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, (Mailbox, bool)> {
+    map(
+        pair(opt(_display_name_flagged::<P>), angle_addr::<P>),
+        |(dname, address)| {
+            let encoded = dname.as_ref().is_some_and(|(_, e)| *e);
+            let dname = dname.map(|(d, _)| d);
+            (Mailbox { dname, address }, encoded)
+        },
+    )(input)
+}
+
+// Write the text of a comment. Encoded words are decoded and the
+// whitespace between adjacent encoded words is dropped, as in
+// `unstructured`.
+fn _write_comment(out: &mut String, content: &[CommentContent<'_>]) {
+    let is_word = |i: Option<usize>| {
+        matches!(
+            i.and_then(|i| content.get(i)),
+            Some(CommentContent::EncodedWord(_))
+        )
+    };
+
+    for (i, cc) in content.iter().enumerate() {
+        match cc {
+            CommentContent::Text(text)
+                if text.trim().is_empty() && is_word(i.checked_sub(1)) && is_word(Some(i + 1)) => {}
+            CommentContent::Text(text) => out.push_str(text),
+            CommentContent::EncodedWord(word) => out.push_str(word),
+            CommentContent::QP(c) => out.push(*c),
+            CommentContent::Comment(nested) => {
+                out.push('(');
+                _write_comment(out, nested);
+                out.push(')');
+            }
+        }
+    }
+}
+
+/// Parse a mailbox, also returning the text of its comments.
+///
+/// Comments are normally discarded. This collects the text of each
+/// top-level comment in order, with surrounding whitespace removed,
+/// such as the name in the legacy `jdoe@example.org (John Doe)` form.
+/// Nested comments are kept in parentheses within the text of the
+/// comment containing them. Encoded words are decoded.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::mailbox_with_comments;
+///
+/// let (_, (mailbox, comments)) =
+///     mailbox_with_comments::<Intl>(b"jdoe@example.org (John Doe)").unwrap();
+/// assert_eq!(mailbox.address.to_string(), "jdoe@example.org");
+/// assert_eq!(comments, ["John Doe"]);
+/// ```
+pub fn mailbox_with_comments<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, (Mailbox, Vec<String>)> {
     alt((
-        name_addr::<P>,
-        map(addr_spec::<P>, |a| Mailbox {
-            dname: None,
-            address: a,
+        map(
+            pair(opt(_display_name_comments::<P>), _angle_addr_comments::<P>),
+            |(dname, (address, comments))| {
+                let (dname, mut out) = match dname {
+                    Some((dname, before)) => (Some(dname), before),
+                    None => (None, Vec::new()),
+                };
+                out.extend(comments);
+                (Mailbox { dname, address }, out)
+            },
+        ),
+        map(_addr_spec_comments::<P>, |(address, comments)| {
+            let dname = None;
+            (Mailbox { dname, address }, comments)
         }),
     ))(input)
 }
 
-fn mailbox_list<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Mailbox>> {
-    separated_list1(tag(","), mailbox::<P>)(input)
+// The text of each comment with surrounding whitespace removed.
+fn _comment_texts<'a>(comments: impl IntoIterator<Item = Vec<CommentContent<'a>>>) -> Vec<String> {
+    comments
+        .into_iter()
+        .map(|content| {
+            let mut text = String::new();
+            _write_comment(&mut text, &content);
+            text.trim().into()
+        })
+        .collect()
 }
 
-fn group_list<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Mailbox>> {
-    alt((mailbox_list::<P>, map(cfws::<P>, |_| vec![])))(input)
+// `parser` surrounded by optional CFWS, also returning the text of
+// the comments.
+fn _with_comments<'a, P: Utf8Policy, O>(
+    parser: impl FnMut(&'a [u8]) -> NomResult<'a, O>,
+) -> impl FnMut(&'a [u8]) -> NomResult<'a, (O, Vec<String>)> {
+    map(
+        tuple((opt(_cfws_comments::<P>), parser, opt(_cfws_comments::<P>))),
+        |(before, out, after)| {
+            let comments = before.into_iter().chain(after).flatten();
+            (out, _comment_texts(comments))
+        },
+    )
 }
 
-fn group<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Group> {
-    map(
-        pair(
-            terminated(display_name::<P>, tag(":")),
-            terminated(opt(group_list::<P>), pair(tag(";"), opt(cfws::<P>))),
+// Like `display_name`, also returning the text of the comments.
+fn _display_name_comments<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, (String, Vec<String>)> {
+    map(many1(word::<P>), |words| {
+        let dname = _concat_words::<P>(&words);
+        let comments = words
+            .into_iter()
+            .flat_map(|w| w.comments_before.into_iter().chain(w.comments_after));
+        (dname, _comment_texts(comments))
+    })(input)
+}
+
+// Like `addr_spec`, also returning the text of the comments.
+fn _addr_spec_comments<P: Utf8Policy>(
+    input: &[u8],
+) -> NomResult<'_, (types::Mailbox, Vec<String>)> {
+    let local_part = alt((
+        map(_with_comments::<P, _>(_dot_atom_text::<P>), |(a, c)| {
+            (a.into(), c)
+        }),
+        map(
+            _with_comments::<P, _>(_inner_quoted_string::<P>),
+            |(qc, c)| {
+                (
+                    LocalPart::Quoted(QuotedString(concat_qs(qc.into_iter()))),
+                    c,
+                )
+            },
         ),
-        |(dname, members)| Group {
-            dname,
-            members: members.unwrap_or_default(),
+    ));
+    let domain = alt((
+        map(_with_comments::<P, _>(_dot_atom_text::<P>), |(a, c)| {
+            (DomainPart::Domain(Domain(a.0)), c)
+        }),
+        map(
+            _with_comments::<P, _>(_domain_literal_text::<P>),
+            |(l, c)| (DomainPart::Address(l), c),
+        ),
+    ));
+
+    map(
+        separated_pair(local_part, tag("@"), domain),
+        |((lp, mut comments), (domain, c))| {
+            comments.extend(c);
+            (types::Mailbox(lp, domain), comments)
         },
     )(input)
 }
 
-fn address<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Address> {
+// Like `angle_addr`, also returning the text of the comments.
+fn _angle_addr_comments<P: Utf8Policy>(
+    input: &[u8],
+) -> NomResult<'_, (types::Mailbox, Vec<String>)> {
+    map(
+        tuple((
+            opt(_cfws_comments::<P>),
+            tag("<"),
+            _angle_addr_route::<P>,
+            _addr_spec_comments::<P>,
+            tag(">"),
+            opt(_cfws_comments::<P>),
+        )),
+        |(before, _, _, (address, inner), _, after)| {
+            let mut comments = _comment_texts(before.into_iter().flatten());
+            comments.extend(inner);
+            comments.extend(_comment_texts(after.into_iter().flatten()));
+            (address, comments)
+        },
+    )(input)
+}
+
+/// Parse a single mailbox with an optional display name.
+///
+/// Accepts both `"John Doe <jdoe@example.org>"` and a bare
+/// [`addr_spec`]. Returns an [`rfc5322::Mailbox`](Mailbox) whose
+/// [`address`](Mailbox::address) field is the [`types::Mailbox`] that
+/// [`addr_spec`] would return.
+///
+/// With a [`Lenient`] policy, an obsolete source route inside the angle
+/// brackets as in `"<@relay.example:jdoe@example.org>"` is skipped.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::mailbox;
+///
+/// let (_, named) = mailbox::<Intl>(b"John Doe <jdoe@example.org>").unwrap();
+/// assert_eq!(named.dname.as_deref(), Some("John Doe"));
+/// assert_eq!(named.address.to_string(), "jdoe@example.org");
+///
+/// let (_, bare) = mailbox::<Intl>(b"jdoe@example.org").unwrap();
+/// assert_eq!(bare.dname, None);
+/// assert_eq!(bare.address, named.address);
+/// ```
+pub fn mailbox<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Mailbox> {
     alt((
-        map(mailbox::<P>, Address::Mailbox),
-        map(group::<P>, Address::Group),
+        name_addr::<P>,
+        map(addr_spec::<P>, |a| Mailbox {
+            dname: None,
+            address: a,
+        }),
     ))(input)
 }
 
-fn address_list<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Address>> {
-    separated_list1(tag(","), address::<P>)(input)
+/// A mailbox whose address could not be parsed by [`try_mailbox`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MailboxParseFailure {
+    /// The decoded display name, if any.
+    pub dname: Option<String>,
+    /// Why the address between the angle brackets was rejected.
+    pub reason: &'static str,
 }
 
-fn address_list_crlf<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Address>> {
-    terminated(address_list::<P>, opt(crlf))(input)
-}
+/// Parse a single mailbox, keeping the display name if the address is
+/// invalid.
+///
+/// Behaves like [`mailbox`] on valid input. Otherwise, if the input
+/// starts with an optional display name followed by `"<"`, everything
+/// up to the closing `">"` is skipped and a [`MailboxParseFailure`]
+/// holding the decoded display name is returned. This is meant for
+/// diagnosing malformed addresses; use [`mailbox`] for validation.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::try_mailbox;
+///
+/// let (rem, parsed) = try_mailbox::<Intl>(b"=?utf-8?q?Jos=C3=A9?= <not an address>, x").unwrap();
+/// assert_eq!(rem, b", x");
+/// assert_eq!(parsed.unwrap_err().dname.as_deref(), Some("José"));
+///
+/// let (_, parsed) = try_mailbox::<Intl>(b"Jane <jane@example.org>").unwrap();
+/// assert!(parsed.is_ok());
+/// ```
+pub fn try_mailbox<P: Utf8Policy>(
+    input: &[u8],
+) -> NomResult<'_, Result<Mailbox, MailboxParseFailure>> {
+    if let Ok((rem, mailbox)) = mailbox::<P>(input) {
+        return Ok((rem, Ok(mailbox)));
+    }
 
-fn address_crlf<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Address> {
-    terminated(address::<P>, opt(crlf))(input)
+    let (rem, dname) = terminated(opt(display_name::<P>), pair(opt(cfws::<P>), tag("<")))(input)?;
+    let (rem, reason) = match rem.iter().position(|&c| c == b'>') {
+        Some(pos) => (&rem[pos + 1..], "Invalid address"),
+        None => (&rem[rem.len()..], "Missing closing \">\""),
+    };
+    let (rem, _) = opt(cfws::<P>)(rem)?;
+
+    Ok((rem, Err(MailboxParseFailure { dname, reason })))
 }
 
-fn _8bit_char(input: &[u8]) -> NomResult<'_, char> {
+/// Parse a comma separated list of one or more elements.
+///
+/// CFWS is allowed around the commas. When `allow_empty` is set, empty
+/// elements are skipped as in the obsolete list syntax of [RFC 5322
+/// section 4.4]. This allows leading, repeated and trailing commas,
+/// but at least one element is still required.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::nom::bytes::complete::tag;
+/// use rustyknife::rfc5322::comma_list;
+///
+/// let (rem, items) = comma_list::<Intl, _, _>(tag("a"), false)(b"a , a,(x)a").unwrap();
+/// assert_eq!((rem, items.len()), (&b""[..], 3));
+/// assert!(comma_list::<Intl, _, _>(tag("a"), false)(b", a").is_err());
+///
+/// let (rem, items) = comma_list::<Intl, _, _>(tag("a"), true)(b", a,, a,").unwrap();
+/// assert_eq!((rem, items.len()), (&b""[..], 2));
+/// ```
+///
+/// [RFC 5322 section 4.4]: https://tools.ietf.org/html/rfc5322#section-4.4
+pub fn comma_list<'a, P: Utf8Policy, O, F>(
+    elem: F,
+    allow_empty: bool,
+) -> impl FnMut(&'a [u8]) -> NomResult<'a, Vec<O>>
+where
+    F: FnMut(&'a [u8]) -> NomResult<'a, O>,
+{
+    let mut elem = elem;
+    let sep = || delimited(opt(cfws::<P>), tag(","), opt(cfws::<P>));
+
+    move |input| {
+        if allow_empty {
+            delimited(
+                many0(sep()),
+                separated_list1(many1(sep()), &mut elem),
+                many0(sep()),
+            )(input)
+        } else {
+            separated_list1(sep(), &mut elem)(input)
+        }
+    }
+}
+
+fn mailbox_list<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Mailbox>> {
+    comma_list::<P, _, _>(mailbox::<P>, false)(input)
+}
+
+fn group_list<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Mailbox>> {
+    alt((mailbox_list::<P>, map(cfws::<P>, |_| vec![])))(input)
+}
+
+/// Parse a named group of mailboxes such as `"Team: a@example.org;"`.
+///
+/// The member list may be empty as in `"Undisclosed recipients:;"`.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::group;
+///
+/// let (_, team) = group::<Intl>(b"Team: a@example.org, Bob <b@example.org>;").unwrap();
+/// assert_eq!(team.dname, "Team");
+/// assert_eq!(team.members.len(), 2);
+///
+/// let (_, empty) = group::<Intl>(b"Undisclosed recipients:;").unwrap();
+/// assert!(empty.members.is_empty());
+/// ```
+pub fn group<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Group> {
+    map(
+        pair(
+            terminated(display_name::<P>, tag(":")),
+            terminated(opt(group_list::<P>), pair(tag(";"), opt(cfws::<P>))),
+        ),
+        |(dname, members)| Group {
+            dname,
+            members: members.unwrap_or_default(),
+        },
+    )(input)
+}
+
+nom_fromstr!(Mailbox, mailbox::<Intl>);
+nom_fromstr!(Group, group::<Intl>);
+nom_fromstr!(Address, address::<Intl>);
+
+fn address<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Address> {
+    alt((
+        map(mailbox::<P>, Address::Mailbox),
+        map(group::<P>, Address::Group),
+    ))(input)
+}
+
+// Some clients such as older versions of Outlook separate addresses
+// with ";". A ";" closing a group is consumed by the group itself.
+fn _address_separator<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, &[u8]> {
+    if P::OPTIONS.lenient {
+        alt((tag(","), tag(";")))(input)
+    } else {
+        tag(",")(input)
+    }
+}
+
+fn address_list<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Address>> {
+    separated_list1(_address_separator::<P>, address::<P>)(input)
+}
+
+fn address_list_crlf<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<Address>> {
+    let trailing = |i| {
+        if P::OPTIONS.lenient {
+            opt(tag(";"))(i)
+        } else {
+            Ok((i, None))
+        }
+    };
+
+    terminated(address_list::<P>, pair(trailing, opt(crlf)))(input)
+}
+
+fn address_crlf<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Address> {
+    terminated(address::<P>, opt(crlf))(input)
+}
+
+fn _8bit_char(input: &[u8]) -> NomResult<'_, char> {
     map(take1_filter(|c| c >= 0x80), |_| '\u{fffd}')(input)
 }
 
+// Plain ASCII without encoded words or folding decodes to itself, so
+// skip the full parser. The only line break allowed is a final CRLF,
+// which is left unconsumed as the full parser would.
+fn _unstructured_ascii(input: &[u8]) -> Option<(&[u8], &str)> {
+    let body = input.strip_suffix(b"\r\n").unwrap_or(input);
+
+    if body
+        .iter()
+        .all(|&c| c == b' ' || c == b'\t' || c.is_ascii_graphic())
+        && !body.windows(2).any(|w| w == b"=?")
+    {
+        Some((&input[body.len()..], str::from_utf8(body).unwrap()))
+    } else {
+        None
+    }
+}
+
 /// Parse an unstructured header such as `"Subject:"`.
 ///
 /// Returns a fully decoded string.
 pub fn unstructured<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
+    match _unstructured_ascii(input) {
+        Some((rem, text)) => Ok((rem, text.into())),
+        None => unstructured_full::<P>(input),
+    }
+}
+
+pub(crate) fn unstructured_full<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
+    _unstructured(map(many1(alt((P::vchar, _8bit_char))), |c| {
+        c.iter().collect::<String>()
+    }))(input)
+}
+
+/// Parse an unstructured header, rejecting unterminated encoded words.
+///
+/// Like [`unstructured`], but fails instead of keeping an encoded word
+/// as literal text when it was started but not terminated. This is the
+/// case when a word contains `"=?"`, a charset, an optional `"*"`
+/// language, `"?"`, `"B"` or `"Q"` and `"?"`, but no valid encoded text
+/// ending in `"?="` follows. For example `"=?utf-8?B?abc"` and
+/// `"=?utf-8?Q?a b?="` are rejected, whereas `"=?"` alone or
+/// `"=?utf-8?X?abc?="` are kept as text as they never started an
+/// encoded word.
+///
+/// Malformed encoded words are sometimes used to evade filters.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::{unstructured, unstructured_strict};
+///
+/// let input = b" Win =?utf-8?B?RlJFRQ money";
+/// assert_eq!(unstructured::<Intl>(input).unwrap().1, " Win =?utf-8?B?RlJFRQ money");
+/// assert!(unstructured_strict::<Intl>(input).is_err());
+///
+/// let (_, decoded) = unstructured_strict::<Intl>(b" =?utf-8?B?RlJFRQ==?= a=?b").unwrap();
+/// assert_eq!(decoded, " FREE a=?b");
+/// ```
+pub fn unstructured_strict<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
+    let text = |i| {
+        let (rem, run) = recognize_many1(alt((P::vchar, _8bit_char)))(i)?;
+        if (0..run.len()).any(|pos| is_unterminated_encoded_word(&run[pos..])) {
+            return Err(nom::Err::Failure(()));
+        }
+        let (_, chars) = many1(alt((P::vchar, _8bit_char)))(run)?;
+
+        Ok((rem, chars.into_iter().collect()))
+    };
+
+    _unstructured(text)(input)
+}
+
+// Unstructured text made of encoded words and runs of text decoded by
+// the given parser.
+fn _unstructured<'a>(
+    text: impl FnMut(&'a [u8]) -> NomResult<'a, String>,
+) -> impl FnMut(&'a [u8]) -> NomResult<'a, String> {
     map(
         pair(
             many0(alt((
@@ -547,12 +1509,7 @@ pub fn unstructured<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
                         },
                     ),
                 ),
-                pair(
-                    ofws,
-                    map(many1(alt((P::vchar, _8bit_char))), |c| {
-                        c.iter().collect::<String>()
-                    }),
-                ),
+                pair(ofws, text),
             ))),
             many0(wsp),
         ),
@@ -565,7 +1522,133 @@ pub fn unstructured<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
             out.push_str(str::from_utf8(&ws).unwrap());
             out
         },
-    )(input)
+    )
+}
+
+/// Decode an unstructured header, assuming a charset for raw 8bit
+/// text.
+///
+/// Like [`unstructured`], but runs of text that the policy does not
+/// accept are decoded with `assumed` instead of the policy's handling
+/// of 8bit bytes. With [`Intl`], this is text that is not valid UTF-8
+/// and with [`Legacy`], any text with 8bit bytes. This is useful when
+/// a sender is known to put raw text in a legacy charset in its
+/// headers. Encoded words are decoded using their declared charset as
+/// usual.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::{Intl, Legacy};
+/// use rustyknife::rfc5322::unstructured_with_charset;
+///
+/// let input = b" caf\xc3\xa9 na\xefve";
+/// let subject = unstructured_with_charset::<Intl>(input, encoding_rs::WINDOWS_1252);
+/// assert_eq!(subject, " caf\u{e9} na\u{ef}ve");
+/// let subject = unstructured_with_charset::<Legacy>(input, encoding_rs::WINDOWS_1252);
+/// assert_eq!(subject, " caf\u{c3}\u{a9} na\u{ef}ve");
+/// ```
+pub fn unstructured_with_charset<P: Utf8Policy>(
+    input: &[u8],
+    assumed: &'static Encoding,
+) -> String {
+    let text = map(
+        recognize_many1(take1_filter(|c| c.is_ascii_graphic() || c >= 0x80)),
+        |run| match nom::exact!(run, many1(P::vchar)) {
+            Ok((_, text)) => text.into_iter().collect(),
+            Err(_) => assumed.decode_without_bom_handling(run).0.into_owned(),
+        },
+    );
+
+    match _unstructured(text)(input) {
+        Ok((_, decoded)) => decoded,
+        Err(_) => String::new(),
+    }
+}
+
+/// Parse an unstructured header and also return the raw value.
+///
+/// Returns the decoded string as [`unstructured`] does, along with the
+/// exact bytes that were matched. This is useful when the original
+/// value is needed for hashing or comparison.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::unstructured_raw;
+///
+/// let (_, (decoded, raw)) = unstructured_raw::<Intl>(b" =?utf-8?q?caf=C3=A9?=").unwrap();
+/// assert_eq!(decoded, " café");
+/// assert_eq!(raw, b" =?utf-8?q?caf=C3=A9?=");
+/// ```
+pub fn unstructured_raw<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, (String, &[u8])> {
+    map(consumed(unstructured::<P>), |(raw, decoded)| (decoded, raw))(input)
+}
+
+/// Parse the content of a `"Comments:"` header.
+///
+/// The content is unstructured text and is decoded as by
+/// [`unstructured`]. Folding is removed.
+pub fn comments<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
+    unstructured::<P>(input)
+}
+
+/// Decode the `"Subject:"` header of a header section.
+///
+/// Finds the first `Subject` header in the output of
+/// [`header_section`], unfolds it and decodes it with
+/// [`unstructured`]. Surrounding whitespace is trimmed. Returns
+/// [`None`] if there is no such header.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::headersection::header_section;
+/// use rustyknife::rfc5322::subject;
+///
+/// let (_, fields) = header_section(b"Subject: =?utf-8?q?caf=C3=A9?=\r\n time\r\n\r\n").unwrap();
+/// assert_eq!(subject::<Intl>(&fields).unwrap(), "caf\u{e9} time");
+/// ```
+///
+/// [`header_section`]: crate::headersection::header_section
+pub fn subject<P: Utf8Policy>(fields: &[HeaderField<'_>]) -> Option<String> {
+    let value = unfold(find_header(fields, "Subject")?);
+    let (_, decoded) = unstructured::<P>(&value).ok()?;
+
+    Some(decoded.trim().to_string())
+}
+
+/// Decode every valid header of a header section.
+///
+/// Yields the name and the unfolded, RFC 2047 decoded value of each
+/// field returned by [`header_section`], in order. The value is
+/// decoded as by [`unstructured`] with surrounding whitespace trimmed,
+/// regardless of the header's actual syntax. Invalid fields are
+/// skipped. The name borrows from the input unless it contains
+/// invalid UTF-8.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::headersection::header_section;
+/// use rustyknife::rfc5322::decoded_headers;
+///
+/// let (_, fields) = header_section(b"Subject: =?utf-8?q?caf=C3=A9?=\r\nbroken\r\nX-Spam: no\r\n\r\n").unwrap();
+/// let headers: Vec<_> = decoded_headers::<Intl>(&fields).collect();
+/// assert_eq!(headers, [("Subject".into(), "caf\u{e9}".into()), ("X-Spam".into(), "no".into())]);
+/// ```
+///
+/// [`header_section`]: crate::headersection::header_section
+pub fn decoded_headers<'a, P: Utf8Policy>(
+    fields: &'a [HeaderField<'a>],
+) -> impl Iterator<Item = (Cow<'a, str>, String)> {
+    fields.iter().flatten().map(|(name, value)| {
+        let value = unfold(value);
+        let decoded = match unstructured::<P>(&value) {
+            Ok((_, decoded)) => decoded.trim().to_string(),
+            Err(_) => String::new(),
+        };
+        (String::from_utf8_lossy(name), decoded)
+    })
 }
 
 /// Parse the content of a `"From:"` header.
@@ -573,16 +1656,202 @@ pub fn unstructured<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, String> {
 /// Returns a list of addresses, since [RFC 6854] allows multiple mail
 /// authors.
 ///
+/// With a [`Lenient`] policy, addresses may also be separated by `";"`
+/// as some clients do. This applies to all address list parsers.
+///
+/// Groups are accepted by default as allowed by [RFC 6854]. With a
+/// [`NoGroupSenders`] policy, only mailboxes are accepted as in RFC
+/// 5322.
+///
 /// [RFC 6854]: https://tools.ietf.org/html/rfc6854
 pub fn from<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<Address>> {
-    address_list_crlf::<P>(i)
+    verify(address_list_crlf::<P>, |list: &[Address]| {
+        !P::OPTIONS.no_group_senders || list.iter().all(|a| matches!(a, Address::Mailbox(_)))
+    })(i)
+}
+
+// Skip over a display name without decoding it.
+fn _skip_phrase<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, &[u8]> {
+    let quoted = recognize(tuple((
+        tag("\""),
+        recognize_many0(alt((
+            recognize(fws),
+            recognize(P::qtext),
+            recognize(quoted_pair::<P>),
+        ))),
+        tag("\""),
+    )));
+
+    recognize_many1(alt((
+        cfws::<P>,
+        recognize_many1(P::atext),
+        quoted,
+        tag("."),
+    )))(input)
+}
+
+fn _first_mailbox_addr<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, types::Mailbox> {
+    alt((
+        preceded(opt(_skip_phrase::<P>), angle_addr::<P>),
+        addr_spec::<P>,
+    ))(input)
+}
+
+/// Get the address of the first mailbox of a `"From:"` header.
+///
+/// Parsing stops after the first address, so the rest of the list is
+/// neither parsed nor validated. Display names are skipped without
+/// being decoded. If the header starts with a group, the address of
+/// its first member is returned.
+///
+/// This is cheaper than [`from`] when only the first sender address
+/// is needed, such as for rate limiting.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::first_addr;
+///
+/// let addr = first_addr::<Intl>(b" =?utf-8?q?Jos=C3=A9?= <jose@example.org>, a@example.org");
+/// assert_eq!(addr.unwrap().to_string(), "jose@example.org");
+/// ```
+pub fn first_addr<P: Utf8Policy>(i: &[u8]) -> Option<types::Mailbox> {
+    alt((
+        _first_mailbox_addr::<P>,
+        preceded(
+            tuple((_skip_phrase::<P>, tag(":"), opt(cfws::<P>))),
+            _first_mailbox_addr::<P>,
+        ),
+    ))(i)
+    .ok()
+    .map(|(_, mailbox)| mailbox)
+}
+
+/// An element of an address list that could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressParseFailure {
+    /// The position of the element in the input.
+    pub range: Range<usize>,
+    /// Why the element was rejected.
+    pub reason: &'static str,
+}
+
+// Split an address list on the commas that separate its elements,
+// skipping over quoted strings, comments, angle addresses, domain
+// literals and groups.
+fn _split_address_list(input: &[u8]) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut comment_depth = 0;
+    let (mut quoted, mut escaped, mut angle, mut literal, mut group) =
+        (false, false, false, false, false);
+
+    for (pos, &c) in input.iter().enumerate() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match c {
+            b'\\' if quoted || comment_depth > 0 => escaped = true,
+            b'"' if comment_depth == 0 => quoted = !quoted,
+            _ if quoted => (),
+            b'(' => comment_depth += 1,
+            b')' if comment_depth > 0 => comment_depth -= 1,
+            _ if comment_depth > 0 => (),
+            b'<' => angle = true,
+            b'>' => angle = false,
+            b'[' => literal = true,
+            b']' => literal = false,
+            _ if angle || literal => (),
+            b':' => group = true,
+            b';' => group = false,
+            b',' if !group => {
+                out.push(start..pos);
+                start = pos + 1;
+            }
+            _ => (),
+        }
+    }
+    out.push(start..input.len());
+
+    out
+}
+
+/// Parse an address list, reporting invalid elements individually.
+///
+/// The list is first split on the commas that are not inside a group,
+/// quoted string or comment and every element is then parsed on its
+/// own. Returns the valid addresses along with the position of every
+/// invalid element. Empty elements are skipped.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::from_partial;
+///
+/// let input = b"a@example.org, not an address, g: b@example.org, c@example.org;\r\n";
+/// let (addrs, failures) = from_partial::<Intl>(input);
+/// assert_eq!(addrs.len(), 2);
+/// assert_eq!(failures[0].range, 14..29);
+/// ```
+pub fn from_partial<P: Utf8Policy>(i: &[u8]) -> (Vec<Address>, Vec<AddressParseFailure>) {
+    let mut addrs = Vec::new();
+    let mut failures = Vec::new();
+
+    for range in _split_address_list(i) {
+        let element = &i[range.clone()];
+        if element.iter().all(|c| c.is_ascii_whitespace()) {
+            continue;
+        }
+
+        let reason = match terminated(address::<P>, pair(many0(wsp), opt(crlf)))(element) {
+            Ok(([], addr)) => {
+                addrs.push(addr);
+                continue;
+            }
+            Ok(_) => "Trailing characters after address",
+            Err(_) => "Invalid address",
+        };
+        failures.push(AddressParseFailure { range, reason });
+    }
+
+    (addrs, failures)
 }
 
 /// Parse the content of a `"Sender:"` header.
 ///
 /// Returns a single address.
+///
+/// As with [`from`], the address may be a group as allowed by [RFC
+/// 6854] unless the policy is [`NoGroupSenders`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::{Intl, NoGroupSenders};
+/// use rustyknife::rfc5322::{sender, Address};
+///
+/// let input = b"Automated Systems:;";
+/// assert!(matches!(sender::<Intl>(input).unwrap().1, Address::Group(_)));
+/// assert!(sender::<NoGroupSenders<Intl>>(input).is_err());
+/// ```
+///
+/// [RFC 6854]: https://tools.ietf.org/html/rfc6854
 pub fn sender<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Address> {
-    address_crlf::<P>(i)
+    verify(address_crlf::<P>, |a| {
+        !P::OPTIONS.no_group_senders || matches!(a, Address::Mailbox(_))
+    })(i)
+}
+
+/// Parse the content of an `"Author:"` header.
+///
+/// The header is defined by [RFC 9057] and identifies the author of
+/// the content when it differs from the `"From:"` sending identity.
+/// Returns a list of addresses, parsed as for [`from`].
+///
+/// [RFC 9057]: https://tools.ietf.org/html/rfc9057
+pub fn author<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<Address>> {
+    address_list_crlf::<P>(i)
 }
 
 /// Parse the content of a `"Reply-To:"` header.
@@ -591,3 +1860,702 @@ pub fn sender<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Address> {
 pub fn reply_to<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<Address>> {
     address_list_crlf::<P>(i)
 }
+
+/// Parse the content of a `"To:"` header.
+///
+/// Returns a list of addresses.
+pub fn to<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<Address>> {
+    address_list_crlf::<P>(i)
+}
+
+/// Parse the content of a `"Cc:"` header.
+///
+/// Returns a list of addresses.
+pub fn cc<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<Address>> {
+    address_list_crlf::<P>(i)
+}
+
+/// Parse the content of a `"Bcc:"` header.
+///
+/// Returns a list of addresses. The header may be empty or contain
+/// only whitespace and comments, in which case the list is empty.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::bcc;
+///
+/// let (rem, addrs) = bcc::<Intl>(b" \r\n").unwrap();
+/// assert!(rem.is_empty());
+/// assert!(addrs.is_empty());
+/// ```
+pub fn bcc<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<Address>> {
+    alt((
+        address_list_crlf::<P>,
+        map(tuple((opt(cfws::<P>), many0(wsp), opt(crlf))), |_| {
+            Vec::new()
+        }),
+    ))(i)
+}
+
+/// A message identifier such as found in a `"Message-ID:"` header.
+///
+/// Holds the canonical `id-left@id-right` form, without the angle
+/// brackets or any CFWS.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MessageId(pub(crate) String);
+string_newtype!(MessageId);
+
+fn _no_fold_literal<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, &[u8]> {
+    delimited(
+        opt(cfws::<P>),
+        recognize(tuple((tag("["), recognize_many0(P::dtext), tag("]")))),
+        opt(cfws::<P>),
+    )(input)
+}
+
+fn msg_id<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, MessageId> {
+    map(
+        delimited(
+            pair(opt(cfws::<P>), tag("<")),
+            separated_pair(
+                dot_atom::<P>,
+                tag("@"),
+                alt((
+                    map(dot_atom::<P>, |a| a.0),
+                    map(_no_fold_literal::<P>, |l| {
+                        String::from_utf8_lossy(l).into_owned()
+                    }),
+                )),
+            ),
+            pair(tag(">"), opt(cfws::<P>)),
+        ),
+        |(left, right)| MessageId(format!("{}@{}", left, right)),
+    )(input)
+}
+
+/// Parse the content of a `"Message-ID:"` header.
+///
+/// Returns the identifier without the angle brackets. Comments and
+/// whitespace around the identifier and its parts are removed.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::message_id;
+///
+/// let (_, id) = message_id::<Intl>(b" (sent by x) <1234.5678@mail.example.org>\r\n").unwrap();
+/// assert_eq!(&*id, "1234.5678@mail.example.org");
+/// ```
+pub fn message_id<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, MessageId> {
+    terminated(msg_id::<P>, opt(crlf))(i)
+}
+
+// Anything between the ids of an obsolete msg-id list.
+fn _obs_id_junk<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Option<MessageId>> {
+    map(
+        alt((
+            map(word::<P>, |_| ()),
+            map(
+                take_while1(|c: u8| !c.is_ascii_whitespace() && c != b'<'),
+                |_| (),
+            ),
+            map(cfws::<P>, |_| ()),
+        )),
+        |_| None,
+    )(input)
+}
+
+// A list of msg-id. With a lenient policy, anything else between the
+// ids is skipped as allowed by the obsolete syntax.
+fn _msg_id_list<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, Vec<MessageId>> {
+    let item = |i| {
+        if P::OPTIONS.lenient {
+            alt((map(msg_id::<P>, Some), _obs_id_junk::<P>))(i)
+        } else {
+            map(msg_id::<P>, Some)(i)
+        }
+    };
+
+    terminated(
+        verify(
+            map(many1(item), |ids| ids.into_iter().flatten().collect()),
+            |ids: &[MessageId]| !ids.is_empty(),
+        ),
+        opt(crlf),
+    )(input)
+}
+
+/// Parse the content of a `"References:"` header.
+///
+/// Returns the message identifiers in order. With a [`Lenient`]
+/// policy, words and other text between the identifiers are skipped
+/// as allowed by the obsolete syntax. At least one identifier is
+/// required.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::{Intl, Lenient};
+/// use rustyknife::rfc5322::references;
+///
+/// let input = b" <a@example.org> and <b@example.org>";
+/// let (rem, ids) = references::<Intl>(input).unwrap();
+/// assert_eq!((rem, ids.len()), (&b"and <b@example.org>"[..], 1));
+/// let (rem, ids) = references::<Lenient<Intl>>(input).unwrap();
+/// assert_eq!((rem, ids.len()), (&b""[..], 2));
+/// ```
+pub fn references<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<MessageId>> {
+    _msg_id_list::<P>(i)
+}
+
+/// Parse the content of an `"In-Reply-To:"` header.
+///
+/// Parsed as for [`references`].
+pub fn in_reply_to<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Vec<MessageId>> {
+    _msg_id_list::<P>(i)
+}
+
+/// The name of a header containing addresses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressHeaderKind {
+    /// `"From:"`
+    From,
+    /// `"Sender:"`
+    Sender,
+    /// `"Reply-To:"`
+    ReplyTo,
+    /// `"To:"`
+    To,
+    /// `"Cc:"`
+    Cc,
+    /// `"Bcc:"`
+    Bcc,
+    /// `"Resent-From:"`
+    ResentFrom,
+    /// `"Resent-Sender:"`
+    ResentSender,
+    /// `"Resent-To:"`
+    ResentTo,
+    /// `"Resent-Cc:"`
+    ResentCc,
+    /// `"Resent-Bcc:"`
+    ResentBcc,
+    /// `"Author:"`
+    Author,
+}
+
+impl AddressHeaderKind {
+    fn from_name(name: &[u8]) -> Option<Self> {
+        use AddressHeaderKind::*;
+
+        Some(match name.to_ascii_lowercase().as_slice() {
+            b"from" => From,
+            b"sender" => Sender,
+            b"reply-to" => ReplyTo,
+            b"to" => To,
+            b"cc" => Cc,
+            b"bcc" => Bcc,
+            b"resent-from" => ResentFrom,
+            b"resent-sender" => ResentSender,
+            b"resent-to" => ResentTo,
+            b"resent-cc" => ResentCc,
+            b"resent-bcc" => ResentBcc,
+            b"author" => Author,
+            _ => return None,
+        })
+    }
+}
+
+fn _address_header_name(input: &[u8]) -> NomResult<'_, AddressHeaderKind> {
+    map_opt(
+        terminated(take_while1(|c| matches!(c, 33..=57 | 59..=126)), tag(":")),
+        AddressHeaderKind::from_name,
+    )(input)
+}
+
+/// Parse a complete address header, including the field name.
+///
+/// The field name selects the grammar used for the value. `"Sender:"`
+/// and `"Resent-Sender:"` yield a single address and `"Bcc:"` and
+/// `"Resent-Bcc:"` may be empty. Other header names are rejected.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::{address_header, AddressHeaderKind};
+///
+/// let (_, (kind, addrs)) = address_header::<Intl>(b"CC: a@example.org, b@example.org\r\n").unwrap();
+/// assert_eq!(kind, AddressHeaderKind::Cc);
+/// assert_eq!(addrs.len(), 2);
+/// ```
+pub fn address_header<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, (AddressHeaderKind, Vec<Address>)> {
+    use AddressHeaderKind::*;
+
+    let (rem, kind) = _address_header_name(i)?;
+    let (rem, addrs) = match kind {
+        Sender | ResentSender => map(address_crlf::<P>, |a| vec![a])(rem)?,
+        Bcc | ResentBcc => bcc::<P>(rem)?,
+        _ => address_list_crlf::<P>(rem)?,
+    };
+
+    Ok((rem, (kind, addrs)))
+}
+
+/// Extract everything that looks like an address from `i`.
+///
+/// This is a heuristic recovery tool for badly malformed headers
+/// that the RFC-conformant parsers such as [`from`] reject. It scans
+/// for `addr-spec` syntax (`local@domain`) anywhere in the input,
+/// ignoring display names, list separators and any other structure.
+/// Results may therefore include things that were never meant as
+/// addresses.
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::extract_addresses;
+///
+/// let addrs = extract_addresses::<Intl>(b"John <john@example.org> ;; <<jane@example.org");
+/// let addrs: Vec<_> = addrs.iter().map(|a| a.to_string()).collect();
+/// assert_eq!(addrs, ["john@example.org", "jane@example.org"]);
+/// ```
+pub fn extract_addresses<P: Utf8Policy>(i: &[u8]) -> Vec<types::Mailbox> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+
+    while pos < i.len() {
+        let at_boundary = pos == 0 || {
+            let c = i[pos - 1];
+            !(c.is_ascii_alphanumeric() || c >= 0x80 || b"!#$%&'*+-/=?^_`{|}~.".contains(&c))
+        };
+
+        if at_boundary {
+            if let Ok((rem, mailbox)) = addr_spec::<P>(&i[pos..]) {
+                out.push(mailbox);
+                pos = i.len() - rem.len();
+                continue;
+            }
+        }
+        pos += 1;
+    }
+
+    out
+}
+
+/// A date and time parsed from a `"Date:"` header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateTimeFields {
+    /// The full year, such as `2003`.
+    pub year: u32,
+    /// The month, from 1 to 12.
+    pub month: u8,
+    /// The day of the month, starting at 1.
+    pub day: u8,
+    /// The hour, from 0 to 23.
+    pub hour: u8,
+    /// The minute, from 0 to 59.
+    pub minute: u8,
+    /// The second, from 0 to 60 to allow for leap seconds.
+    pub second: u8,
+    /// The offset from UTC in seconds, positive east of UTC.
+    pub offset: i32,
+}
+
+impl DateTimeFields {
+    fn new(
+        year: u32,
+        month: u8,
+        day: u8,
+        (hour, minute, second): (u8, u8, u8),
+        offset: i32,
+    ) -> Option<Self> {
+        let leap =
+            year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let month_days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+
+        if day == 0 || day > month_days || hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+
+        Some(DateTimeFields {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            offset,
+        })
+    }
+
+    /// The number of seconds since the Unix epoch.
+    ///
+    /// Leap seconds are counted as the first second of the next
+    /// minute.
+    pub fn timestamp(&self) -> i64 {
+        // Days from civil, see http://howardhinnant.github.io/date_algorithms.html
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let doy =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+
+        days * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+            - i64::from(self.offset)
+    }
+}
+
+/// Conversion to a [`chrono::DateTime`] with the parsed offset.
+///
+/// Fails if the offset is a day or more, which [`chrono`] does not
+/// support. A leap second is represented the way [`chrono`] does.
+///
+/// # Examples
+/// ```
+/// use chrono::{DateTime, FixedOffset};
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::date;
+/// use std::convert::TryFrom;
+///
+/// let (_, parsed) = date::<Intl>(b" Fri, 21 Nov 1997 09:55:06 -0600").unwrap();
+/// let dt = DateTime::<FixedOffset>::try_from(parsed).unwrap();
+/// assert_eq!(dt.to_rfc2822(), "Fri, 21 Nov 1997 09:55:06 -0600");
+/// ```
+#[cfg(feature = "chrono")]
+impl std::convert::TryFrom<DateTimeFields> for chrono::DateTime<chrono::FixedOffset> {
+    type Error = ();
+
+    fn try_from(value: DateTimeFields) -> Result<Self, Self::Error> {
+        use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+        let offset = FixedOffset::east_opt(value.offset).ok_or(())?;
+        let (second, nano) = match value.second {
+            60 => (59, 1_000_000_000),
+            s => (s, 0),
+        };
+        let naive = NaiveDate::from_ymd_opt(
+            i32::try_from(value.year).map_err(|_| ())?,
+            value.month.into(),
+            value.day.into(),
+        )
+        .and_then(|d| {
+            d.and_hms_nano_opt(value.hour.into(), value.minute.into(), second.into(), nano)
+        })
+        .ok_or(())?;
+
+        offset.from_local_datetime(&naive).single().ok_or(())
+    }
+}
+
+const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+fn _name_index(names: &'static [&'static str]) -> impl Fn(&[u8]) -> NomResult<'_, usize> {
+    move |input| {
+        map_opt(take(3usize), |n: &[u8]| {
+            names
+                .iter()
+                .position(|x| x.as_bytes().eq_ignore_ascii_case(n))
+        })(input)
+    }
+}
+
+fn _digits(min: usize, max: usize) -> impl Fn(&[u8]) -> NomResult<'_, u32> {
+    move |input| {
+        map_opt(take_while_m_n(min, max, |c: u8| c.is_ascii_digit()), |d| {
+            parse_u64_bounded(d, u32::MAX.into()).map(|n| n as u32)
+        })(input)
+    }
+}
+
+fn _day_of_week(input: &[u8]) -> NomResult<'_, usize> {
+    terminated(preceded(opt(fws), _name_index(&DAY_NAMES)), tag(","))(input)
+}
+
+fn _time_of_day(input: &[u8]) -> NomResult<'_, (u8, u8, u8)> {
+    map(
+        tuple((
+            _digits(2, 2),
+            preceded(tag(":"), _digits(2, 2)),
+            opt(preceded(tag(":"), _digits(2, 2))),
+        )),
+        |(h, m, s)| (h as u8, m as u8, s.unwrap_or(0) as u8),
+    )(input)
+}
+
+fn _zone(input: &[u8]) -> NomResult<'_, i32> {
+    map_opt(
+        pair(alt((tag("+"), tag("-"))), _digits(4, 4)),
+        |(sign, hhmm)| {
+            if hhmm % 100 > 59 {
+                return None;
+            }
+            let offset = (hhmm / 100 * 3600 + hhmm % 100 * 60) as i32;
+            Some(if sign == b"-" { -offset } else { offset })
+        },
+    )(input)
+}
+
+fn date_time<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DateTimeFields> {
+    map_opt(
+        terminated(
+            tuple((
+                opt(_day_of_week),
+                preceded(opt(fws), _digits(1, 2)),
+                preceded(fws, _name_index(&MONTH_NAMES)),
+                delimited(fws, _digits(4, 9), fws),
+                _time_of_day,
+                preceded(fws, _zone),
+            )),
+            opt(cfws::<P>),
+        ),
+        |(_, day, month, year, time, zone)| {
+            DateTimeFields::new(year, month as u8 + 1, day as u8, time, zone)
+        },
+    )(input)
+}
+
+fn _obs_year(input: &[u8]) -> NomResult<'_, u32> {
+    map(consumed(_digits(2, 9)), |(digits, year)| {
+        match digits.len() {
+            2 if year < 50 => year + 2000,
+            2 | 3 => year + 1900,
+            _ => year,
+        }
+    })(input)
+}
+
+fn _obs_zone(input: &[u8]) -> NomResult<'_, i32> {
+    const ZONES: [(&str, i32); 10] = [
+        ("UT", 0),
+        ("GMT", 0),
+        ("EST", -5),
+        ("EDT", -4),
+        ("CST", -6),
+        ("CDT", -5),
+        ("MST", -7),
+        ("MDT", -6),
+        ("PST", -8),
+        ("PDT", -7),
+    ];
+
+    alt((
+        map_opt(
+            take_while1(|c: u8| c.is_ascii_alphabetic()),
+            |z: &[u8]| {
+                match ZONES
+                    .iter()
+                    .find(|(n, _)| n.as_bytes().eq_ignore_ascii_case(z))
+                {
+                    Some((_, hours)) => Some(hours * 3600),
+                    // Military zones were defined with the wrong sign and
+                    // must be treated as -0000.
+                    None if z.len() == 1 && !z.eq_ignore_ascii_case(b"J") => Some(0),
+                    None => None,
+                }
+            },
+        ),
+        _zone,
+    ))(input)
+}
+
+// Surround an obsolete date component with optional CFWS.
+fn _obs<'a, P: Utf8Policy, O>(
+    parser: impl FnMut(&'a [u8]) -> NomResult<'a, O>,
+) -> impl FnMut(&'a [u8]) -> NomResult<'a, O> {
+    delimited(opt(cfws::<P>), parser, opt(cfws::<P>))
+}
+
+// The obsolete date-time syntax with the given zone parser. A missing
+// zone is taken as UTC.
+fn _obs_date_time_with<'a, P: Utf8Policy>(
+    zone: impl FnMut(&'a [u8]) -> NomResult<'a, Option<i32>>,
+) -> impl FnMut(&'a [u8]) -> NomResult<'a, DateTimeFields> {
+    map_opt(
+        tuple((
+            opt(terminated(_obs::<P, _>(_name_index(&DAY_NAMES)), tag(","))),
+            _obs::<P, _>(_digits(1, 2)),
+            _obs::<P, _>(_name_index(&MONTH_NAMES)),
+            _obs::<P, _>(_obs_year),
+            _obs::<P, _>(_digits(2, 2)),
+            preceded(tag(":"), _obs::<P, _>(_digits(2, 2))),
+            opt(preceded(tag(":"), _obs::<P, _>(_digits(2, 2)))),
+            _obs::<P, _>(zone),
+        )),
+        |(_, day, month, year, hour, minute, second, zone)| {
+            let time = (hour as u8, minute as u8, second.unwrap_or(0) as u8);
+            DateTimeFields::new(year, month as u8 + 1, day as u8, time, zone.unwrap_or(0))
+        },
+    )
+}
+
+fn obs_date_time<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DateTimeFields> {
+    _obs_date_time_with::<P>(map(_obs_zone, Some))(input)
+}
+
+fn _any_zone(input: &[u8]) -> NomResult<'_, i32> {
+    alt((map(tag_no_case("UTC"), |_| 0), _obs_zone))(input)
+}
+
+// The C asctime() format, optionally with a zone before or after the
+// year as printed by date(1).
+fn _asctime<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, DateTimeFields> {
+    map_opt(
+        tuple((
+            opt(terminated(
+                _obs::<P, _>(_name_index(&DAY_NAMES)),
+                opt(tag(",")),
+            )),
+            _obs::<P, _>(_name_index(&MONTH_NAMES)),
+            _obs::<P, _>(_digits(1, 2)),
+            _obs::<P, _>(_time_of_day),
+            opt(_obs::<P, _>(_any_zone)),
+            _obs::<P, _>(_digits(4, 4)),
+            opt(_obs::<P, _>(_any_zone)),
+        )),
+        |(_, month, day, time, zone_before, year, zone_after)| {
+            let zone = zone_before.or(zone_after).unwrap_or(0);
+            DateTimeFields::new(year, month as u8 + 1, day as u8, time, zone)
+        },
+    )(input)
+}
+
+/// Parse the content of a `"Date:"` header.
+///
+/// Out of range values such as month 13 or hour 25 are rejected. The
+/// day of the week is not checked against the date.
+///
+/// The obsolete syntax of [RFC 5322 section 4.3] is also accepted, as
+/// parsers are required to. This allows CFWS between every component,
+/// two and three digit years and alphabetic time zones such as `GMT`
+/// and `EST`.
+///
+/// [RFC 5322 section 4.3]: https://tools.ietf.org/html/rfc5322#section-4.3
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::date;
+///
+/// let (_, parsed) = date::<Intl>(b" Fri, 21 Nov 1997 09:55:06 -0600\r\n").unwrap();
+/// assert_eq!(parsed.timestamp(), 880127706);
+///
+/// let (_, obsolete) = date::<Intl>(b" Fri, 21 Nov 97 15:55:06 GMT\r\n").unwrap();
+/// assert_eq!(obsolete.timestamp(), parsed.timestamp());
+/// ```
+pub fn date<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, DateTimeFields> {
+    terminated(alt((date_time::<P>, obs_date_time::<P>)), opt(crlf))(i)
+}
+
+/// Parse a date in any of the formats commonly found in mail.
+///
+/// This is a best effort superset of [`date`] for archives and
+/// appliances that don't follow the standard. Any header containing a
+/// date such as `"Resent-Date:"` may be parsed with it. Accepted
+/// formats are:
+///
+///  * The [RFC 5322] `date-time`, such as `Fri, 21 Nov 1997 09:55:06 -0600`.
+///  * The obsolete syntax also accepted by [`date`].
+///  * The same with a `UTC` zone or no zone at all.
+///  * The C `asctime()` format, such as `Sun Jan  1 00:00:00 2020`, with
+///    an optional zone before or after the year.
+///
+/// A missing zone is taken to be UTC, like `-0000`.
+///
+/// [RFC 5322]: https://tools.ietf.org/html/rfc5322#section-3.3
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc5322::any_date;
+///
+/// let (_, strict) = any_date(b" Sun, 01 Jan 2020 00:00:00 +0000").unwrap();
+/// let (_, asctime) = any_date(b" Sun Jan  1 00:00:00 2020").unwrap();
+/// let (_, zoneless) = any_date(b" 1 Jan 2020 00:00").unwrap();
+/// assert_eq!(strict.timestamp(), 1577836800);
+/// assert_eq!(asctime, strict);
+/// assert_eq!(zoneless, strict);
+/// ```
+pub fn any_date(i: &[u8]) -> NomResult<'_, DateTimeFields> {
+    type P = Lenient<Intl>;
+
+    terminated(
+        alt((
+            date_time::<P>,
+            _obs_date_time_with::<P>(opt(_any_zone)),
+            _asctime::<P>,
+        )),
+        opt(crlf),
+    )(i)
+}
+
+enum ReceivedToken {
+    For,
+    Addr(types::Mailbox),
+    Other,
+}
+
+fn _received_token<P: Utf8Policy>(input: &[u8]) -> NomResult<'_, ReceivedToken> {
+    alt((
+        map(angle_addr::<P>, ReceivedToken::Addr),
+        map(addr_spec::<P>, ReceivedToken::Addr),
+        map(word::<P>, |w| match w.text {
+            Text::Atom(a) if a.eq_ignore_ascii_case("for") => ReceivedToken::For,
+            _ => ReceivedToken::Other,
+        }),
+        map(domain::<P>, |_| ReceivedToken::Other),
+        // Tolerate anything else that real world servers put here.
+        map(take1_filter(|c| c != b';'), |_| ReceivedToken::Other),
+    ))(input)
+}
+
+/// Parse the tokens of a `"Received:"` header and return the
+/// recipient from its `for` clause.
+///
+/// Returns `None` if there is no `for` clause. When several addresses
+/// follow `for`, the first one is returned. Parsing stops after the
+/// `";"` that precedes the date, so the remaining input can be passed
+/// to [`date`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::behaviour::Intl;
+/// use rustyknife::rfc5322::{date, received_for};
+///
+/// let input = b" from mx.example.org (mx.example.org [192.0.2.1])\r\n by mail.example.com with ESMTPS id 4Xyz\r\n for <jdoe@example.com>; Thu, 13 Feb 1997 08:00:00 +0100\r\n";
+/// let (rem, rcpt) = received_for::<Intl>(input).unwrap();
+/// assert_eq!(rcpt.unwrap().to_string(), "jdoe@example.com");
+/// assert!(date::<Intl>(rem).is_ok());
+/// ```
+pub fn received_for<P: Utf8Policy>(i: &[u8]) -> NomResult<'_, Option<types::Mailbox>> {
+    map(
+        terminated(
+            many0(preceded(opt(cfws::<P>), _received_token::<P>)),
+            pair(opt(cfws::<P>), tag(";")),
+        ),
+        |tokens| {
+            let mut after_for = false;
+            for token in tokens {
+                match token {
+                    ReceivedToken::For => after_for = true,
+                    ReceivedToken::Addr(addr) if after_for => return Some(addr),
+                    _ => after_for = false,
+                }
+            }
+            None
+        },
+    )(i)
+}
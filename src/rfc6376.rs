@@ -0,0 +1,119 @@
+//! [DKIM] canonicalization
+//!
+//! [DKIM]: https://tools.ietf.org/html/rfc6376
+
+use crate::headersection::unfold;
+
+fn is_wsp(c: u8) -> bool {
+    c == b' ' || c == b'\t'
+}
+
+// Replace runs of WSP by a single space and remove trailing WSP.
+fn compress_wsp(input: &[u8], out: &mut Vec<u8>) {
+    let mut pending_space = false;
+
+    for &c in input {
+        if is_wsp(c) {
+            pending_space = true;
+        } else {
+            if pending_space {
+                out.push(b' ');
+                pending_space = false;
+            }
+            out.push(c);
+        }
+    }
+}
+
+/// Apply the DKIM `relaxed` header canonicalization.
+///
+/// `name` and `value` are a header as split by
+/// [`header_section`]. The name is lowercased, the value is unfolded,
+/// runs of whitespace are reduced to a single space and whitespace
+/// around the colon and at the end of the value is removed, as
+/// described in [RFC 6376 section 3.4.2].
+///
+/// The result has no trailing CRLF, which must be added for every
+/// header but the `DKIM-Signature` being verified. For the minimal
+/// transformation see [`unfold`].
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc6376::canonicalize_relaxed;
+///
+/// assert_eq!(canonicalize_relaxed(b"Subject", b" Hello\r\n\t  world  "), b"subject:Hello world");
+/// ```
+///
+/// [`header_section`]: crate::headersection::header_section
+/// [RFC 6376 section 3.4.2]: https://tools.ietf.org/html/rfc6376#section-3.4.2
+pub fn canonicalize_relaxed(name: &[u8], value: &[u8]) -> Vec<u8> {
+    let name = name.trim_ascii_end();
+    let value = unfold(value);
+    let value = value.trim_ascii_start();
+
+    let mut out = Vec::with_capacity(name.len() + value.len() + 1);
+    out.extend(name.iter().map(u8::to_ascii_lowercase));
+    out.push(b':');
+    compress_wsp(value, &mut out);
+
+    out
+}
+
+/// Apply the DKIM `simple` or `relaxed` body canonicalization.
+///
+/// Empty lines at the end of the body are removed and the last line is
+/// terminated by a CRLF. In `relaxed` mode, whitespace at the end of
+/// each line is removed and other runs of whitespace are reduced to a
+/// single space. See [RFC 6376 section 3.4.3] and [section 3.4.4].
+///
+/// An empty body canonicalizes to a single CRLF in `simple` mode and
+/// to nothing in `relaxed` mode.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc6376::canonicalize_body;
+///
+/// assert_eq!(canonicalize_body(b"Hi \r\n\r\n\r\n", false), b"Hi \r\n");
+/// assert_eq!(canonicalize_body(b"Hi \r\n\r\n\r\n", true), b"Hi\r\n");
+/// ```
+///
+/// [RFC 6376 section 3.4.3]: https://tools.ietf.org/html/rfc6376#section-3.4.3
+/// [section 3.4.4]: https://tools.ietf.org/html/rfc6376#section-3.4.4
+pub fn canonicalize_body(body: &[u8], relaxed: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 2);
+    let mut line = Vec::new();
+    let mut empty_lines = 0;
+    let mut rem = body;
+
+    while !rem.is_empty() {
+        let (cur, next) = match rem.windows(2).position(|w| w == b"\r\n") {
+            Some(end) => (&rem[..end], &rem[end + 2..]),
+            None => (rem, &rem[rem.len()..]),
+        };
+        rem = next;
+
+        line.clear();
+        if relaxed {
+            compress_wsp(cur, &mut line);
+        } else {
+            line.extend_from_slice(cur);
+        }
+
+        if line.is_empty() {
+            empty_lines += 1;
+        } else {
+            for _ in 0..empty_lines {
+                out.extend_from_slice(b"\r\n");
+            }
+            empty_lines = 0;
+            out.extend_from_slice(&line);
+            out.extend_from_slice(b"\r\n");
+        }
+    }
+
+    if out.is_empty() && !relaxed {
+        out.extend_from_slice(b"\r\n");
+    }
+
+    out
+}
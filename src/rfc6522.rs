@@ -0,0 +1,49 @@
+//! [Multipart/report] helpers
+//!
+//! [Multipart/report]: https://tools.ietf.org/html/rfc6522
+
+/// The kind of report carried by a `multipart/report` message.
+///
+/// Taken from the `report-type` parameter of the Content-Type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReportType {
+    /// A delivery status notification ([RFC 3464]).
+    ///
+    /// [RFC 3464]: https://tools.ietf.org/html/rfc3464
+    DeliveryStatus,
+    /// A message disposition notification ([RFC 8098]).
+    ///
+    /// [RFC 8098]: https://tools.ietf.org/html/rfc8098
+    DispositionNotification,
+    /// Any other report type, lowercased.
+    Other(String),
+}
+
+/// Get the `report-type` from the parameters of a `multipart/report`
+/// Content-Type.
+///
+/// The parameters are those returned by [`content_type`]. The
+/// parameter name and value are matched case-insensitively. Returns
+/// [`None`] if there is no `report-type` parameter.
+///
+/// # Examples
+/// ```
+/// use rustyknife::rfc2231::content_type;
+/// use rustyknife::rfc6522::{report_type, ReportType};
+///
+/// let (_, (_, params)) = content_type(b"multipart/report; report-type=delivery-status; boundary=x").unwrap();
+/// assert_eq!(report_type(&params), Some(ReportType::DeliveryStatus));
+/// ```
+///
+/// [`content_type`]: crate::rfc2231::content_type
+pub fn report_type(params: &[(String, String)]) -> Option<ReportType> {
+    let (_, value) = params
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("report-type"))?;
+
+    Some(match value.to_ascii_lowercase().as_str() {
+        "delivery-status" => ReportType::DeliveryStatus,
+        "disposition-notification" => ReportType::DispositionNotification,
+        other => ReportType::Other(other.into()),
+    })
+}
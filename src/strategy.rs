@@ -0,0 +1,94 @@
+//! Property testing strategies
+//!
+//! [`proptest`](mod@proptest) strategies generating valid addresses, for testing code
+//! that formats or parses them. Requires the `proptest` feature.
+//!
+//! Every generated value is valid for both [RFC 5321] and [RFC 5322],
+//! so formatting it with [`Display`](std::fmt::Display) and parsing it
+//! back gives an equal value.
+//!
+//! [RFC 5321]: https://tools.ietf.org/html/rfc5321
+//! [RFC 5322]: https://tools.ietf.org/html/rfc5322
+
+use crate::rfc5322::{Address, Group, Mailbox};
+use crate::types::{self, AddressLiteral, DomainPart, DotAtom, LocalPart, QuotedString};
+use proptest::prelude::*;
+use std::net::IpAddr;
+
+/// A DNS domain name of one to four labels.
+///
+/// Labels never contain consecutive hyphens, which IDNA reserves.
+pub fn domain() -> impl Strategy<Value = types::Domain> {
+    prop::collection::vec("[a-z0-9]{1,8}(-[a-z0-9]{1,8}){0,2}", 1..=4)
+        .prop_map(|labels| types::Domain(labels.join(".")))
+}
+
+/// An IPv4 or IPv6 address literal.
+pub fn address_literal() -> impl Strategy<Value = AddressLiteral> {
+    any::<IpAddr>().prop_map(AddressLiteral::IP)
+}
+
+/// A domain part, mostly domain names.
+pub fn domain_part() -> impl Strategy<Value = DomainPart> {
+    prop_oneof![
+        4 => domain().prop_map(DomainPart::Domain),
+        1 => address_literal().prop_map(DomainPart::Address),
+    ]
+}
+
+/// Atoms separated by single periods.
+pub fn dot_atom() -> impl Strategy<Value = DotAtom> {
+    prop::collection::vec("[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]{1,10}", 1..=4)
+        .prop_map(|atoms| DotAtom(atoms.join(".")))
+}
+
+/// A quoted string of printable ASCII and spaces.
+///
+/// This includes `"` and `\`, which must be escaped when quoted.
+pub fn quoted_string() -> impl Strategy<Value = QuotedString> {
+    "[ -~]{0,20}".prop_map(QuotedString)
+}
+
+/// A local part, mostly dot atoms.
+pub fn local_part() -> impl Strategy<Value = LocalPart> {
+    prop_oneof![
+        3 => dot_atom().prop_map(LocalPart::DotAtom),
+        1 => quoted_string().prop_map(LocalPart::Quoted),
+    ]
+}
+
+/// An email address.
+pub fn mailbox() -> impl Strategy<Value = types::Mailbox> {
+    (local_part(), domain_part()).prop_map(|(lp, dp)| types::Mailbox(lp, dp))
+}
+
+/// A display name of words separated by single spaces.
+///
+/// Words may contain any non-whitespace character, including
+/// specials and non-ASCII text.
+pub fn display_name() -> impl Strategy<Value = String> {
+    prop::collection::vec("[^\\s\\p{Cc}]{1,8}", 1..=4).prop_map(|words| words.join(" "))
+}
+
+/// A mailbox with an optional display name.
+pub fn named_mailbox() -> impl Strategy<Value = Mailbox> {
+    (prop::option::of(display_name()), mailbox())
+        .prop_map(|(dname, address)| Mailbox { dname, address })
+}
+
+/// A group of up to four mailboxes.
+pub fn group() -> impl Strategy<Value = Group> {
+    (
+        display_name(),
+        prop::collection::vec(named_mailbox(), 0..=4),
+    )
+        .prop_map(|(dname, members)| Group { dname, members })
+}
+
+/// A mailbox or group address.
+pub fn address() -> impl Strategy<Value = Address> {
+    prop_oneof![
+        3 => named_mailbox().prop_map(Address::Mailbox),
+        1 => group().prop_map(Address::Group),
+    ]
+}
@@ -1,4 +1,5 @@
 use crate::headersection::*;
+use std::borrow::Cow;
 
 fn hs(i: &[u8]) -> Vec<HeaderField<'_>> {
     let (rem, parsed) = header_section(i).unwrap();
@@ -88,3 +89,194 @@ fn big_garbage() {
         ]
     );
 }
+
+#[test]
+fn unfold_borrowed() {
+    assert!(matches!(
+        unfold(b" no folding"),
+        Cow::Borrowed(b" no folding")
+    ));
+    assert_eq!(&*unfold(b" a\r\n\tb\r\n  c"), b" a\tb  c");
+}
+
+#[test]
+fn field_ref() {
+    let parsed = hs(b"To: a@example.org,\r\n b@example.org\r\nbroken\r\n\r\n");
+    let field = HeaderFieldRef::from(parsed[0].unwrap());
+    assert_eq!(field.name(), b"To");
+    assert_eq!(field.raw_value(), b" a@example.org,\r\n b@example.org");
+    assert_eq!(&*field.value(), b" a@example.org, b@example.org");
+    assert_eq!(parsed[1].map(HeaderFieldRef::from), Err(b"broken".as_ref()));
+}
+
+#[test]
+fn requires_utf8() {
+    let parsed = hs(b"To: a@example.org\r\nX-Broken \xc3\xa9\r\n\r\n");
+    assert!(message_requires_utf8(&parsed));
+    assert!(!message_requires_utf8(&parsed[..1]));
+}
+
+#[test]
+fn find_first_header() {
+    let fields = hs(b"X-A: 1\r\nbroken line\r\nx-a: 2\r\n\r\n");
+    assert_eq!(find_header(&fields, "X-A"), Some(&b" 1"[..]));
+    assert_eq!(find_header(&fields, "broken line"), None);
+}
+
+fn emit_and_split(name: &str, value: &HeaderValue) -> (String, Vec<u8>) {
+    let line = emit_header(name, value).unwrap();
+    assert!(line.lines().all(|l| l.len() <= 78), "{}", line);
+    let section = format!("{}\r\n", line);
+    let fields = hs(section.as_bytes());
+    assert_eq!(fields.len(), 1);
+    let (n, v) = fields[0].unwrap();
+    (
+        String::from_utf8(n.to_vec()).unwrap(),
+        unfold(v).into_owned(),
+    )
+}
+
+#[test]
+fn emit_unstructured_roundtrip() {
+    let text = "Réunion du comité: ordre du jour, budget et planning des vacances d'été pour toute l'équipe";
+    let (name, value) = emit_and_split("Subject", &HeaderValue::Unstructured(text.into()));
+    assert_eq!(name, "Subject");
+    let (_, decoded) = crate::rfc5322::unstructured::<crate::behaviour::Intl>(&value).unwrap();
+    assert_eq!(decoded.trim(), text);
+}
+
+#[test]
+fn emit_unstructured_mostly_ascii() {
+    let text = "Re: your invoice for the café order";
+    let line = emit_header("Subject", &HeaderValue::Unstructured(text.into())).unwrap();
+    assert!(
+        line.starts_with("Subject: =?utf-8?q?Re=3A_your_invoice"),
+        "{}",
+        line
+    );
+    let (_, value) = emit_and_split("Subject", &HeaderValue::Unstructured(text.into()));
+    assert_eq!(crate::rfc2047::decode_rfc2047(&value).trim(), text);
+
+    let text = "日本語".repeat(10);
+    let line = emit_header("Subject", &HeaderValue::Unstructured(text.clone())).unwrap();
+    assert!(line.starts_with("Subject: =?utf-8?b?"), "{}", line);
+    let (_, value) = emit_and_split("Subject", &HeaderValue::Unstructured(text.clone()));
+    assert_eq!(crate::rfc2047::decode_rfc2047(&value).trim(), text);
+}
+
+#[test]
+fn emit_unstructured_double_space() {
+    let text = format!("{}  {}", "a".repeat(69), "b".repeat(77));
+    let line = emit_header("Subject", &HeaderValue::Unstructured(text.clone())).unwrap();
+    assert!(
+        line.split("\r\n")
+            .skip(1)
+            .all(|l| l.is_empty() || !l.trim().is_empty()),
+        "{:?}",
+        line
+    );
+    let section = format!("{}\r\n", line);
+    let fields = hs(section.as_bytes());
+    let (_, value) = fields[0].unwrap();
+    assert_eq!(&*unfold(value), format!(" {}", text).as_bytes());
+}
+
+#[test]
+fn emit_addresses_roundtrip() {
+    use crate::rfc5322::{from, Address, Mailbox};
+
+    let addresses: Vec<Address> = (0..5)
+        .map(|i| {
+            Address::Mailbox(Mailbox {
+                dname: Some(format!("Person \"{}\" Müller", i)),
+                address: format!("person{}@example.org", i).parse().unwrap(),
+            })
+        })
+        .collect();
+    let (_, value) = emit_and_split("To", &HeaderValue::Addresses(addresses.clone()));
+    let (_, parsed) = from::<crate::behaviour::Intl>(&value).unwrap();
+    assert_eq!(parsed, addresses);
+}
+
+#[test]
+fn emit_content_type_roundtrip() {
+    let params = vec![
+        ("charset".to_string(), "utf-8".to_string()),
+        ("name".to_string(), "my report.pdf".to_string()),
+        ("filename".to_string(), "café \"final\".pdf".to_string()),
+    ];
+    let (_, value) = emit_and_split(
+        "Content-Type",
+        &HeaderValue::ContentType {
+            mime_type: "application/pdf".into(),
+            params: params.clone(),
+        },
+    );
+    let (_, (mime_type, mut parsed)) = crate::rfc2231::content_type(&value).unwrap();
+    assert_eq!(mime_type, "application/pdf");
+    crate::rfc2231::sort_params(&mut parsed);
+    let mut params = params;
+    crate::rfc2231::sort_params(&mut params);
+    assert_eq!(parsed, params);
+}
+
+#[test]
+fn emit_rejects_injection() {
+    let text = HeaderValue::Unstructured("hi".into());
+    for name in ["X-A\r\nBcc", "", "X A", "X:A"] {
+        assert_eq!(emit_header(name, &text), Err("invalid header name"));
+    }
+
+    let content_type = |mime_type: &str, param: &str| HeaderValue::ContentType {
+        mime_type: mime_type.into(),
+        params: vec![(param.into(), "x".into())],
+    };
+    for mime_type in ["text/plain\r\nBcc: x@y", "text", "text/", "te xt/plain"] {
+        assert_eq!(
+            emit_header("Content-Type", &content_type(mime_type, "name")),
+            Err("invalid MIME type")
+        );
+    }
+    for param in ["a\r\nBcc", "", "a=b", "a*"] {
+        assert_eq!(
+            emit_header("Content-Type", &content_type("text/plain", param)),
+            Err("invalid parameter name")
+        );
+    }
+}
+
+#[test]
+fn eight_bit_field_name() {
+    let input = b"X-Gr\xfc\xdfe: hallo\r\nSubject: hi\r\n\r\n";
+    assert_eq!(hs(input)[0], Err(&b"X-Gr\xfc\xdfe: hallo"[..]));
+
+    let (rem, parsed) = header_section_lenient(input).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        parsed,
+        [
+            Ok((&b"X-Gr\xfc\xdfe"[..], &b" hallo"[..])),
+            Ok((&b"Subject"[..], &b" hi"[..]))
+        ]
+    );
+    assert_eq!(
+        header_lenient(input).unwrap().1,
+        Some(Ok((&b"X-Gr\xfc\xdfe"[..], &b" hallo"[..])))
+    );
+}
+
+#[test]
+fn nul_in_value() {
+    let parsed = hs(b"Subject: hi\x00there\r\nX-Folded: a\r\n b\x00c\r\nTo: a@example.org\r\n\r\n");
+    assert_eq!(
+        parsed,
+        [
+            Err(&b"Subject: hi\x00there"[..]),
+            Err(&b"X-Folded: a\r\n b\x00c"[..]),
+            Ok((&b"To"[..], &b" a@example.org"[..]))
+        ]
+    );
+
+    let (_, parsed) = header_section_lenient(b"X-\xe9: \x00\r\n\r\n").unwrap();
+    assert_eq!(parsed, [Err(&b"X-\xe9: \x00"[..])]);
+}
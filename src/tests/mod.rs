@@ -1,4 +1,19 @@
 mod headersection;
+mod nonstandard;
+#[cfg(feature = "std-io")]
+mod reader;
+mod rfc1864;
+mod rfc2045;
+mod rfc2047;
 mod rfc2231;
+mod rfc2369;
+mod rfc3461;
+mod rfc3463;
+mod rfc3676;
 mod rfc5321;
 mod rfc5322;
+mod rfc6376;
+mod rfc6522;
+#[cfg(feature = "proptest")]
+mod strategy;
+mod types;
@@ -0,0 +1,60 @@
+use crate::behaviour::Intl;
+use crate::nonstandard::*;
+
+#[test]
+fn loop_header() {
+    let (rem, parsed) = x_loop::<Intl>(b" vacation@example.org ").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed, "vacation@example.org");
+}
+
+#[test]
+fn mailing_list_entries() {
+    let list =
+        mailing_list::<Intl>(b" contact group-owner@example.org; list group@example.org").unwrap();
+    assert_eq!(list.to_string(), "group@example.org");
+    assert_eq!(mailing_list::<Intl>(b" run by ezmlm"), None);
+}
+
+#[test]
+fn delivered_to_cfws() {
+    let (rem, parsed) = delivered_to::<Intl>(b" (local) user+tag@example.org (final) ").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.to_string(), "user+tag@example.org");
+    assert!(delivered_to::<Intl>(b" <user@example.org>").is_err());
+
+    let (rem, parsed) = delivered_to::<Intl>(b" user@example.org\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.to_string(), "user@example.org");
+}
+
+#[test]
+fn x_original_to_cfws() {
+    let (rem, parsed) = x_original_to::<Intl>(b" \r\n\t(alias) Info@Example.org (x)").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.to_string(), "Info@Example.org");
+    assert!(x_original_to::<Intl>(b" Info <info@example.org>").is_err());
+
+    let (rem, parsed) = x_original_to::<Intl>(b" info@example.org (x)\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.to_string(), "info@example.org");
+}
+
+#[test]
+fn thread_headers() {
+    let (rem, topic) = thread_topic::<Intl>(b" =?utf-8?q?R=C3=A9union?= budget ").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(topic, "Réunion budget");
+
+    let (rem, index) = thread_index(b" AdPj0+Rl5ixGH2ZbSjKlhLi/yF4AqA==\r\n").unwrap();
+    assert_eq!(rem, b"\r\n");
+    assert_eq!(index.len(), 22);
+    assert_eq!(&index[..3], [0x01, 0xd3, 0xe3]);
+    assert!(thread_index(b" not*base64").is_err());
+}
+
+#[test]
+fn unpadded_thread_index() {
+    let (_, index) = thread_index(b" AQHT\r\n dA").unwrap();
+    assert_eq!(index, [0x01, 0x01, 0xd3, 0x74]);
+}
@@ -0,0 +1,60 @@
+use crate::reader::*;
+use std::io::{self, Read};
+
+// Returns the input a few bytes at a time.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.0.len().min(buf.len()).min(3);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn quoted_printable_body() {
+    let message = b"Subject: test\r\n folded\r\nContent-Transfer-Encoding: Quoted-Printable\r\n\r\ncaf=C3=A9  =\r\nau lait\r\n";
+    let mut reader = MessageReader::new(Trickle(message)).unwrap();
+    assert_eq!(
+        reader.headers(),
+        [
+            Ok((&b"Subject"[..], &b" test\r\n folded"[..])),
+            Ok((&b"Content-Transfer-Encoding"[..], &b" Quoted-Printable"[..]))
+        ]
+    );
+
+    let mut body = Vec::new();
+    reader.read_to_end(&mut body).unwrap();
+    assert_eq!(body, "café  au lait\r\n".as_bytes());
+}
+
+#[test]
+fn header_only_and_limits() {
+    let mut reader = MessageReader::new(&b"Subject: no body\r\n"[..]).unwrap();
+    assert_eq!(reader.headers().len(), 1);
+    assert_eq!(reader.read(&mut [0; 16]).unwrap(), 0);
+
+    let err = MessageReader::new(&b"Subject: cut"[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    let large = format!("Subject: {}\r\n\r\n", "x".repeat(100_000));
+    let err = MessageReader::new(large.as_bytes()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(MessageReader::with_max_header_size(large.as_bytes(), 200_000).is_ok());
+
+    // Smaller than a single read.
+    let message = b"Subject: hello\r\nTo: a@example.org\r\n\r\nbody";
+    let err = MessageReader::with_max_header_size(&message[..], 20).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let reader = MessageReader::with_max_header_size(&message[..], 37).unwrap();
+    assert_eq!(reader.headers().len(), 2);
+    let err = MessageReader::with_max_header_size(&message[..], 36).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let message = b"Subject: hello\r\n";
+    assert!(MessageReader::with_max_header_size(&message[..], 16).is_ok());
+    let err = MessageReader::with_max_header_size(&message[..], 15).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,14 @@
+use crate::rfc1864::*;
+
+#[test]
+fn wrapped_content_md5() {
+    let (rem, digest) = content_md5(b" Q2hlY2sgSW50\r\n\tZWdyaXR5IQ\r\n").unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(&digest, b"Check Integrity!");
+}
+
+#[test]
+fn invalid_content_md5() {
+    assert!(content_md5(b" Q2hlY2sgSW50ZWdyaXR5").is_err());
+    assert!(content_md5(b" Q2hlY2sg*W50ZWdyaXR5IQ==").is_err());
+}
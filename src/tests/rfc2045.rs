@@ -0,0 +1,61 @@
+use crate::headersection::header_section;
+use crate::rfc2045::*;
+
+#[test]
+fn qp_malformed() {
+    assert_eq!(decode_quoted_printable(b"a=3d=ZZ=\nb=\r\n=4"), b"a==ZZb=4");
+    assert_eq!(decode_quoted_printable(b"x \t\ny"), b"x\ny");
+}
+
+#[test]
+fn base64_unpadded() {
+    assert_eq!(decode_base64(b"Zm9v\r\nYg"), b"foob");
+    assert_eq!(decode_base64(b"Zm9vY"), b"foo");
+}
+
+#[test]
+fn body_binary_and_default() {
+    let (body, headers) = header_section(
+        b"Content-Type: application/octet-stream\r\nContent-Transfer-Encoding: BASE64\r\n\r\nAAEC\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        decode_body(&headers, body),
+        DecodedBody::Binary(vec![0, 1, 2])
+    );
+
+    let (body, headers) = header_section(b"Subject: no MIME\r\n\r\n\xe9t\xe9").unwrap();
+    assert_eq!(decode_body(&headers, body), DecodedBody::Text("été".into()));
+}
+
+#[test]
+fn qp_streaming() {
+    let input: &[u8] = b"a=3Db  \r\nsoft=\r\nbreak =\r\n==3D\r\nend=";
+    let expected = decode_quoted_printable(input);
+
+    for split in 0..=input.len() {
+        let mut decoder = QpDecoder::new();
+        let mut out = decoder.update(&input[..split]);
+        out.extend(decoder.update(&input[split..]));
+        out.extend(decoder.finalize());
+        assert_eq!(out, expected, "split at {}", split);
+    }
+
+    let mut decoder = QpDecoder::new();
+    assert_eq!(decoder.update(b"x="), b"x");
+    assert_eq!(decoder.update(b"3"), b"");
+    assert_eq!(decoder.update(b"Dy"), b"=y");
+    assert_eq!(decoder.finalize(), b"");
+}
+
+#[test]
+fn base64_streaming() {
+    let input = b"SGVs\r\nbG8s=IHdv\r\ncmxkIQ";
+    for split in 0..input.len() {
+        let mut decoder = Base64Decoder::new();
+        let mut out = decoder.update(&input[..split]);
+        out.extend(decoder.update(&input[split..]));
+        out.extend(decoder.finalize());
+        assert_eq!(out, decode_base64(input), "split at {}", split);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::headersection::header_section;
+use crate::rfc2047::*;
+use encoding_rs::UTF_8;
+
+#[test]
+fn charsets() {
+    let (_, fields) = header_section(b"Subject: =?ISO-8859-1?Q?caf=E9?= and =?utf-8?b?w6k=?=\r\nbad header =?koi8-r?B?aaa?=\r\nX-Test: =?utf-8?Q?a?= =?broken =?Windows-1252?Q?b?=\r\n\r\n").unwrap();
+    assert_eq!(
+        header_charsets(&fields),
+        ["iso-8859-1", "utf-8", "windows-1252"]
+    );
+}
+
+#[test]
+fn no_charsets() {
+    let (_, fields) = header_section(b"Subject: plain =? text ?=\r\n\r\n").unwrap();
+    assert!(header_charsets(&fields).is_empty());
+}
+
+#[test]
+fn charset_raw() {
+    let (_, word) = encoded_word(b"=?X-Unknown_Charset?Q?abc?=").unwrap();
+    assert_eq!(word.charset_raw(), b"X-Unknown_Charset");
+    assert_eq!(word.decode(), "abc");
+}
+
+#[test]
+fn needs_encoding() {
+    assert!(!header_needs_encoding(""));
+    assert!(!header_needs_encoding("tab\tand ~printable~"));
+    assert!(header_needs_encoding("bell\x07"));
+    assert!(header_needs_encoding("del\x7f"));
+    assert!(header_needs_encoding("looks like =?utf-8?q?x?="));
+}
+
+#[test]
+fn charset_with_dot() {
+    let (rem, word) = encoded_word(b"=?ansi_x3.4-1968?Q?plain_text?=").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(word.charset_raw(), b"ansi_x3.4-1968");
+    assert_eq!(word.decode(), "plain text");
+
+    let (_, word) = encoded_word(b"=?ISO-8859-1*fr?Q?caf=E9?=").unwrap();
+    assert_eq!(word.charset_raw(), b"ISO-8859-1");
+    assert_eq!(word.decode(), "café");
+}
+
+#[test]
+fn gratuitous_encoding() {
+    let gratuitous = |i: &[u8]| encoded_word(i).unwrap().1.is_gratuitous();
+
+    assert!(gratuitous(b"=?utf-8?B?Q2hlYXAgbWVkcw==?="));
+    assert!(gratuitous(b"=?iso-8859-1?q?Cheap_meds!?="));
+    assert!(!gratuitous(b"=?iso-8859-1?q?caf=E9?="));
+    assert!(!gratuitous(b"=?utf-8?q?two=0D=0Alines?="));
+    assert!(!gratuitous(b"=?utf-8?q?=3D=3Fnested?="));
+}
+
+#[test]
+fn search_normalization() {
+    assert_eq!(normalize_for_search(" \r\n "), "");
+    assert_eq!(
+        normalize_for_search("ΣΟΦΟΣ\u{a0} ΟΔΟΣ"),
+        normalize_for_search("σοφος οδος")
+    );
+
+    let (_, decoded) = crate::rfc5322::unstructured::<crate::behaviour::Intl>(
+        b" Fwd: =?utf-8?q?R=C3=A9sum=C3=A9?=\r\n\tFINAL",
+    )
+    .unwrap();
+    #[cfg(feature = "unicode-normalization")]
+    assert_eq!(normalize_for_search(&decoded), "fwd: resume final");
+    #[cfg(not(feature = "unicode-normalization"))]
+    assert_eq!(normalize_for_search(&decoded), "fwd: résumé final");
+}
+
+#[test]
+fn encode_word_roundtrip() {
+    use encoding_rs::{ISO_2022_JP, SHIFT_JIS, WINDOWS_1252};
+
+    let long = "Ünïcödé ".repeat(20) + "忍法写メ光飛ばし(笑) and some = ? _ text";
+    for text in ["a", "Café", "=?utf-8?q?x?=", long.as_str()] {
+        for charset in [UTF_8, WINDOWS_1252, SHIFT_JIS, ISO_2022_JP] {
+            for encoding in [WordEncoding::B, WordEncoding::Q] {
+                let encoded = encode_word(text, charset, encoding);
+                let mut decoded = String::new();
+                for word in encoded.split(' ') {
+                    assert!(word.len() <= 75, "{}", word);
+                    let (rem, word) = encoded_word(word.as_bytes()).unwrap();
+                    assert!(rem.is_empty());
+                    decoded.push_str(&word.decode());
+                }
+                assert_eq!(decoded, text, "{} {:?}", charset.name(), encoding);
+            }
+        }
+    }
+
+    assert_eq!(encode_word("", UTF_8, WordEncoding::Q), "");
+    // Characters that windows-1252 lacks fall back to UTF-8.
+    assert!(encode_word("€ and 光", WINDOWS_1252, WordEncoding::B).starts_with("=?utf-8?b?"));
+}
+
+#[test]
+fn encode_word_auto_picks_shortest() {
+    let ascii = "Re: Your invoice for the café order";
+    let encoded = encode_word_auto(ascii);
+    assert!(encoded.starts_with("=?utf-8?q?"), "{}", encoded);
+    assert!(encoded.len() < encode_word(ascii, UTF_8, WordEncoding::B).len());
+
+    let cjk = "会議の議題について確認";
+    let encoded = encode_word_auto(cjk);
+    assert!(encoded.starts_with("=?utf-8?b?"), "{}", encoded);
+    assert!(encoded.len() < encode_word(cjk, UTF_8, WordEncoding::Q).len());
+
+    assert_eq!(encode_word_auto("Plain subject"), "Plain subject");
+    assert_ne!(
+        encode_word_auto("looks =?like?= a word"),
+        "looks =?like?= a word"
+    );
+}
+
+#[test]
+fn charset_and_language() {
+    let (_, word) = encoded_word(b"=?ISO-8859-1*fr?Q?=E9t=E9?=").unwrap();
+    assert_eq!(word.charset(), "ISO-8859-1");
+    assert_eq!(word.language(), Some("fr"));
+    assert_eq!(word.decode(), "été");
+
+    let (_, word) = encoded_word(b"=?utf-8?b?w6k=?=").unwrap();
+    assert_eq!(word.language(), None);
+}
+
+#[test]
+fn strict_decode() {
+    let cases: &[(&[u8], Result<&str, DecodeError>)] = &[
+        (b"=?utf-8?b?w6k=?=", Ok("é")),
+        (b"=?ISO-8859-1?Q?a=E9_b?=", Ok("aé b")),
+        (b"=?nonsense?b?w6k=?=", Err(DecodeError::UnknownCharset)),
+        (b"=?utf-8?b?w6k!?=", Err(DecodeError::InvalidEncoding)),
+        (b"=?utf-8?q?a=ZZ?=", Err(DecodeError::InvalidEncoding)),
+        (b"=?utf-8?q?a=E?=", Err(DecodeError::InvalidEncoding)),
+        (b"=?utf-8?x?abc?=", Err(DecodeError::InvalidEncoding)),
+        (b"=?utf-8?q?=C3?=", Err(DecodeError::MalformedText)),
+        (b"=?shift_jis?b?gf8=?=", Err(DecodeError::MalformedText)),
+    ];
+
+    for (input, expected) in cases {
+        let (_, word) = encoded_word(input).unwrap();
+        assert_eq!(
+            word.try_decode().as_deref(),
+            expected.as_deref(),
+            "{:?}",
+            word
+        );
+        // The lossy decoding always succeeds.
+        word.decode();
+    }
+}
+
+#[test]
+fn decode_whole_string() {
+    assert_eq!(
+        decode_rfc2047(b"=?utf-8?b?5pel5pys?=\r\n =?shift_jis?b?jOo=?= and =?windows-1252?q?=80?="),
+        "日本語 and €"
+    );
+    // Whitespace is kept next to literal text.
+    assert_eq!(decode_rfc2047(b" x =?utf-8?q?a?= y "), " x a y ");
+    // Malformed words and stray markers are kept as is.
+    assert_eq!(
+        decode_rfc2047(b"=?utf-8?q?a b?= =?=?utf-8?q?c?=?"),
+        "=?utf-8?q?a b?= =?c?"
+    );
+    assert_eq!(decode_rfc2047(b"caf\xe9"), "caf\u{fffd}");
+    assert_eq!(decode_rfc2047(b""), "");
+}
+
+#[test]
+fn fold_long_subject() {
+    let subject = "来週の定例会議の議題と資料の確認についてのお願いです。ご確認ください";
+    let folded = fold_header("Subject", subject);
+
+    let lines: Vec<_> = folded.strip_suffix("\r\n").unwrap().split("\r\n").collect();
+    assert_eq!(lines.len(), 3, "{}", folded);
+    for line in &lines {
+        assert!(line.len() <= 76, "{}", line);
+    }
+    assert!(lines[0].starts_with("Subject: =?utf-8?b?"));
+
+    let section = format!("{}\r\n", folded);
+    let (_, fields) = header_section(section.as_bytes()).unwrap();
+    let (_, value) = fields[0].unwrap();
+    assert_eq!(decode_rfc2047(value).trim_start(), subject);
+
+    assert_eq!(
+        fold_header("Subject", "Hello world"),
+        "Subject: Hello world\r\n"
+    );
+}
@@ -304,3 +304,317 @@ fn attfnbrokentokenutf() {
     let (rem, _) = content_disposition(b"attachment; filename=foo-\xC3\xA4.html").unwrap();
     assert_eq!(rem.len(), 0);
 }
+
+#[test]
+fn disposition_encoded_word() {
+    let (rem, (disp, params)) =
+        content_disposition(b"attachment; filename=\"=?utf-8?B?w6l0w6kucGRm?=\"").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(disp, CD::Attachment);
+    #[cfg(feature = "quoted-string-rfc2047")]
+    assert_eq!(params, [("filename".into(), "été.pdf".into())]);
+    #[cfg(not(feature = "quoted-string-rfc2047"))]
+    assert_eq!(
+        params,
+        [("filename".into(), "=?utf-8?B?w6l0w6kucGRm?=".into())]
+    );
+}
+
+#[test]
+fn sort_params_canonical() {
+    let mut params = vec![
+        ("format".into(), "flowed".into()),
+        ("Charset".into(), "utf-8".into()),
+        ("DelSp".into(), "yes".into()),
+        ("charset".into(), "us-ascii".into()),
+    ];
+    sort_params(&mut params);
+    assert_eq!(
+        params,
+        [
+            ("Charset".into(), "utf-8".into()),
+            ("charset".into(), "us-ascii".into()),
+            ("DelSp".into(), "yes".into()),
+            ("format".into(), "flowed".into()),
+        ]
+    );
+}
+
+#[test]
+fn disposition_dates_parsed() {
+    let (_, (_, params)) = content_disposition(
+        b"inline; read-date=\"Thu, 13 Feb 1997 08:00:00 +0100\"; modification-date=\"31 Feb 1997 00:00:00 +0000\"",
+    )
+    .unwrap();
+    let dates = disposition_dates(&params);
+    assert_eq!(dates.creation, None);
+    assert_eq!(dates.modification, None);
+    let read = dates.read.unwrap();
+    assert_eq!((read.year, read.month, read.day), (1997, 2, 13));
+    assert_eq!(read.offset, 3600);
+}
+
+#[test]
+fn lenient_no_semicolon() {
+    let input = b"text/plain charset=utf-8 format=flowed\r\n";
+    let (rem, (_, params)) = content_type(input).unwrap();
+    assert_eq!(rem, b"charset=utf-8 format=flowed\r\n");
+    assert!(params.is_empty());
+
+    let lenient = ParameterOptions {
+        lenient: true,
+        ..Default::default()
+    };
+    let (rem, (mtype, mut params)) = content_type_with(lenient)(input).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(mtype, "text/plain");
+    params.sort();
+    assert_eq!(
+        params,
+        [
+            ("charset".into(), "utf-8".into()),
+            ("format".into(), "flowed".into())
+        ]
+    );
+}
+
+#[test]
+fn token_charset() {
+    assert_eq!(mime_token(b"a.b*c'd%e!~"), Ok((&b""[..], "a.b*c'd%e!~")));
+    for c in b"()<>@,;:\\\"/[]?= \x7f\x00".iter() {
+        assert!(mime_token(&[*c]).is_err(), "{}", c);
+    }
+}
+
+#[test]
+fn content_type_equivalence() {
+    assert!(content_type_eq(
+        b"text/plain;\r\n Format=flowed;charset=US-ASCII",
+        b"TEXT/plain; charset=\"us-ascii\"; format=flowed"
+    ));
+    assert!(!content_type_eq(
+        b"text/plain; format=flowed",
+        b"text/plain; format=Flowed"
+    ));
+    assert!(!content_type_eq(b"text/plain; a=b", b"text/plain"));
+    assert!(!content_type_eq(b"/", b"/"));
+
+    let a = b"multipart/alternative; boundary=\"=_a\"";
+    let b = b"multipart/alternative; boundary=\"=_b\"";
+    assert!(!content_type_eq(a, b));
+    assert!(content_type_eq_ignoring(a, b, &["Boundary"]));
+}
+
+#[test]
+fn message_partial() {
+    let params = |input: &[u8]| content_type(input).unwrap().1 .1;
+
+    assert_eq!(
+        message_partial_params(&params(b"message/partial; number=1; id=\"a@b\"")),
+        Some(MessagePartial {
+            id: "a@b".into(),
+            number: 1,
+            total: None
+        })
+    );
+    assert_eq!(
+        message_partial_params(&params(b"message/partial; id=x; number=0")),
+        None
+    );
+    assert_eq!(
+        message_partial_params(&params(b"message/partial; id=x; number=3; total=2")),
+        None
+    );
+    assert_eq!(
+        message_partial_params(&params(b"message/partial; id=x; number=+1")),
+        None
+    );
+    assert_eq!(
+        message_partial_params(&params(b"message/partial; number=1")),
+        None
+    );
+}
+
+#[test]
+fn large_section_numbers() {
+    let (_, (_, params)) =
+        content_type(b"text/plain; name*0=a; name*99999999=b; name*1=c").unwrap();
+    assert_eq!(params, [("name".into(), "acb".into())]);
+
+    // Section numbers have at most 8 digits.
+    let (rem, _) = content_type(b"text/plain; name*0=a; name*123456789=b").unwrap();
+    assert!(!rem.is_empty());
+}
+
+#[test]
+fn duplicate_parameters() {
+    let input = b"text/plain; charset=utf-8; format=flowed; Charset=iso-8859-1";
+    for _ in 0..8 {
+        let (_, (_, params)) = content_type(input).unwrap();
+        let charset = params.iter().find(|(n, _)| n == "charset").unwrap();
+        assert_eq!(charset.1, "utf-8");
+    }
+
+    let strict = ParameterOptions {
+        reject_duplicates: true,
+        ..Default::default()
+    };
+    assert!(content_type_with(strict)(input).is_err());
+    assert!(content_disposition_with(strict)(b"attachment; filename=a; filename=b").is_err());
+
+    // The extended form is a distinct parameter and takes precedence.
+    let (_, (_, params)) =
+        content_type_with(strict)(b"text/plain; name=a; name*=utf-8''%C3%A9").unwrap();
+    assert_eq!(params, [("name".into(), "é".into())]);
+}
+
+#[test]
+fn encode_param_continuations() {
+    let value = "Prévisions budgétaires du deuxième trimestre pour l'équipe de développement.pdf";
+    let encoded = encode_param("filename", value, "utf-8");
+    assert!(encoded.starts_with("filename*0*=utf-8''"));
+    assert!(encoded.split("; ").all(|s| s.len() <= 76), "{}", encoded);
+
+    let header = format!("attachment; {}", encoded.replace("; ", ";\r\n "));
+    let (_, cd) = content_disposition(header.as_bytes()).unwrap();
+    assert_eq!(cd.1, [("filename".into(), value.into())]);
+}
+
+#[test]
+fn encode_param_roundtrip() {
+    for value in [
+        "plain",
+        "",
+        "with \"quotes\" and \\",
+        "x".repeat(100).as_str(),
+        "tab\there",
+    ] {
+        let encoded = encode_param("name", value, "utf-8");
+        let (_, (_, params)) = content_type(format!("text/plain; {}", encoded).as_bytes()).unwrap();
+        assert_eq!(params, [("name".into(), value.into())], "{}", encoded);
+    }
+}
+
+#[test]
+fn encode_param_charset_fallback() {
+    for (value, charset, expected) in [
+        (
+            "日本.txt",
+            "iso-8859-1",
+            "name*=utf-8''%E6%97%A5%E6%9C%AC.txt",
+        ),
+        ("café", "bogus", "name*=utf-8''caf%C3%A9"),
+        ("café", "utf-16", "name*=utf-8''caf%C3%A9"),
+        ("café", "utf-8 ", "name*=utf-8''caf%C3%A9"),
+        ("café", "Latin2", "name*=iso-8859-2''caf%E9"),
+    ] {
+        let encoded = encode_param("name", value, charset);
+        assert_eq!(encoded, expected);
+        let (_, (_, params)) = content_type(format!("text/plain; {}", encoded).as_bytes()).unwrap();
+        assert_eq!(params, [("name".into(), value.into())]);
+    }
+}
+
+#[test]
+fn smart_quoted_filename() {
+    let input = "attachment; filename=“Annual report 2023.pdf”; size=1234".as_bytes();
+    let lenient = ParameterOptions {
+        lenient: true,
+        ..Default::default()
+    };
+
+    let (rem, (disposition, params)) = content_disposition_with(lenient)(input).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(disposition, CD::Attachment);
+    let mut params = params;
+    sort_params(&mut params);
+    assert_eq!(
+        params,
+        [
+            ("filename".into(), "Annual report 2023.pdf".into()),
+            ("size".into(), "1234".into())
+        ]
+    );
+
+    let (rem, (_, params)) = content_disposition(input).unwrap();
+    assert!(!rem.is_empty());
+    assert!(params.is_empty());
+}
+
+#[test]
+fn too_many_segments() {
+    let mut input = String::from("attachment; size=10; filename*0*=utf-8''%41");
+    for i in 1..5000 {
+        input.push_str(&format!("; filename*{}*=%41", i));
+    }
+
+    let (rem, (_, params)) = content_disposition(input.as_bytes()).unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(params, [("size".into(), "10".into())]);
+
+    let opts = ParameterOptions {
+        max_segments: 5000,
+        ..Default::default()
+    };
+    let (_, (_, mut params)) = content_disposition_with(opts)(input.as_bytes()).unwrap();
+    sort_params(&mut params);
+    assert_eq!(params[0], ("filename".into(), "A".repeat(5000)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_roundtrip() {
+    let cte = [CTE::QuotedPrintable, CTE::Extended("uuencode".into())];
+    let json = serde_json::to_string(&cte).unwrap();
+    assert_eq!(json, r#"["quoted-printable","x-uuencode"]"#);
+    assert_eq!(serde_json::from_str::<Vec<CTE>>(&json).unwrap(), cte);
+
+    let disposition: CD = serde_json::from_str(r#""Attachment""#).unwrap();
+    assert_eq!(disposition, CD::Attachment);
+    assert!(serde_json::from_str::<CD>(r#""in line""#).is_err());
+}
+
+#[test]
+fn encode_content_type_roundtrip() {
+    let params = vec![
+        ("charset".to_string(), "us-ascii".to_string()),
+        (
+            "name".to_string(),
+            "quarterly report (final).txt".to_string(),
+        ),
+        ("title".to_string(), "Ünïcödé ".repeat(12)),
+        ("x-quote".to_string(), "a \"quoted\" \\ value".to_string()),
+    ];
+    let encoded = encode_content_type("text/plain", &params);
+    assert!(encoded.contains("; title*0*=utf-8''%C3%9Cn"), "{}", encoded);
+    assert!(encoded.contains("; title*1*="), "{}", encoded);
+
+    let (rem, (mime_type, mut parsed)) = content_type(encoded.as_bytes()).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(mime_type, "text/plain");
+    parsed.sort();
+    assert_eq!(parsed, params);
+}
+
+#[test]
+fn content_type_ordered_params() {
+    let (rem, (mime_type, params, duplicates)) = content_type_ordered(
+        b"text/plain; Name*1=\"b.txt\"; charset=utf-8; name*0=a; x=1; charset=us-ascii; X=2",
+    )
+    .unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(mime_type, "text/plain");
+    assert_eq!(
+        params,
+        [
+            ("name".into(), "ab.txt".into()),
+            ("charset".into(), "utf-8".into()),
+            ("x".into(), "1".into()),
+        ]
+    );
+    assert_eq!(duplicates, ["charset", "x"]);
+
+    let (_, (_, params, duplicates)) = content_type_ordered(b"text/plain").unwrap();
+    assert!(params.is_empty());
+    assert!(duplicates.is_empty());
+}
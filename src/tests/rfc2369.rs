@@ -0,0 +1,38 @@
+use crate::rfc2369::*;
+
+#[test]
+fn archive() {
+    let (rem, parsed) = list_archive(
+        b" <https://example.org/\r\n archive/> (web),\r\n <mailto:archive@example.org?subject=index>\r\n",
+    )
+    .unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        parsed,
+        [
+            UnsubTarget::Http("https://example.org/archive/".into()),
+            UnsubTarget::Mailto("mailto:archive@example.org?subject=index".into()),
+        ]
+    );
+}
+
+#[test]
+fn post_no() {
+    let (rem, parsed) = list_post(b" NO\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed, None);
+
+    let (rem, parsed) = list_post(b" <ftp://example.org/post>\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        parsed,
+        Some(vec![UnsubTarget::Other("ftp://example.org/post".into())])
+    );
+
+    let (rem, parsed) = list_post(b" NO(closed)").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed, None);
+
+    assert!(list_post(b" NOT-A-URL\r\n").is_err());
+    assert!(list_post(b" NO<mailto:list@example.org>").is_err());
+}
@@ -0,0 +1,50 @@
+use crate::rfc3461::*;
+
+#[test]
+fn rcpt_params() {
+    let (params, other) = dsn_rcpt_params(&[("NOTIFY", Some("SUCCESS,DELAY"))]).unwrap();
+    assert!(other.is_empty());
+    assert_eq!(
+        params.notify,
+        Some(Notify {
+            on_success: true,
+            on_failure: false,
+            delay: true,
+        })
+    );
+    assert_eq!(params.orcpt, None);
+
+    assert_eq!(
+        dsn_rcpt_params(&[("NOTIFY", Some("NEVER,SUCCESS"))]),
+        Err("Invalid NOTIFY")
+    );
+}
+
+#[test]
+fn orcpt_length() {
+    let max = format!("rfc822;{}@example.org", "a".repeat(500 - 19));
+    assert_eq!(max.len(), 500);
+    assert!(dsn_rcpt_params(&[("ORCPT", Some(&max))]).is_ok());
+
+    let over = format!("rfc822;{}@example.org", "a".repeat(500 - 18));
+    assert_eq!(
+        dsn_rcpt_params(&[("ORCPT", Some(&over))]),
+        Err("ORCPT over 500 bytes")
+    );
+}
+
+#[test]
+fn original_recipient_header() {
+    let (rem, parsed) = original_recipient(b" rfc822 ;\r\n bob+2B@example.org \r\n").unwrap();
+    assert!(rem.is_empty());
+    assert_eq!(parsed, ("rfc822".into(), "bob+2B@example.org".into()));
+
+    let parsed = original_recipient(" UTF-8; δοκιμή@παράδειγμα.δοκιμή".as_bytes())
+        .unwrap()
+        .1;
+    assert_eq!(parsed, ("UTF-8".into(), "δοκιμή@παράδειγμα.δοκιμή".into()));
+
+    assert!(original_recipient(" rfc822; jöran@example.org".as_bytes()).is_err());
+    assert!(original_recipient(b" rfc822; ").is_err());
+    assert!(original_recipient(b" bob@example.org").is_err());
+}
@@ -0,0 +1,33 @@
+use crate::rfc3463::*;
+
+#[test]
+fn classify() {
+    let (rem, status) = enhanced_status(b"5.1.1 user unknown").unwrap();
+    assert_eq!(rem, b" user unknown");
+    assert_eq!(
+        status,
+        EnhancedStatus {
+            class: 5,
+            subject: 1,
+            detail: 1
+        }
+    );
+    assert!(status.is_permanent() && !status.is_transient() && !status.is_success());
+
+    let (_, status) = enhanced_status(b"2.0.0").unwrap();
+    assert!(status.is_success());
+    let (_, status) = enhanced_status(b"4.999.999").unwrap();
+    assert!(status.is_transient());
+}
+
+#[test]
+fn out_of_range() {
+    assert!(enhanced_status(b"1.0.0").is_err());
+    assert!(enhanced_status(b"5..0").is_err());
+    assert!(enhanced_status(b"5.1").is_err());
+    // The subject and detail have at most three digits.
+    assert_eq!(
+        enhanced_status(b"5.1000.0").unwrap_err(),
+        nom::Err::Error(())
+    );
+}
@@ -0,0 +1,35 @@
+use crate::rfc2231::content_type;
+use crate::rfc3676::*;
+
+#[test]
+fn params() {
+    let (_, (_, params)) = content_type(b"text/plain; charset=utf-8; Format=Flowed").unwrap();
+    assert_eq!(
+        flowed_params(&params),
+        FlowedParams {
+            flowed: true,
+            delsp: false
+        }
+    );
+}
+
+#[test]
+fn params_absent() {
+    let (_, (_, params)) = content_type(b"text/plain; delsp=no").unwrap();
+    assert_eq!(flowed_params(&params), FlowedParams::default());
+}
+
+#[test]
+fn unflow_quoted() {
+    let body = "> a quoted \r\n> line\r\n>> nested\r\n>>\r\nreply \r\ntext\r\n";
+    assert_eq!(
+        unflow(body, false),
+        "> a quoted line\n>> nested\n>>\nreply text\n"
+    );
+}
+
+#[test]
+fn unflow_delsp_signature() {
+    let body = "Hello, wor \r\nld.\r\n-- \r\nsig\r\n";
+    assert_eq!(unflow(body, true), "Hello, world.\n-- \nsig\n");
+}
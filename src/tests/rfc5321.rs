@@ -1,6 +1,7 @@
 use crate::behaviour::*;
 use crate::rfc5321::*;
 use crate::types::*;
+use std::borrow::Cow;
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::str::FromStr;
@@ -143,3 +144,150 @@ fn normal_quoted_lp() {
     lp.smtp_try_unquote();
     assert_eq!(lp, LocalPart::Quoted(QuotedString("a b".into())));
 }
+
+#[test]
+fn comment_rejected() {
+    assert!(Mailbox::from_smtp(b"user@host (comment)").is_err());
+    assert!(Mailbox::from_smtp(b"user(comment)@host").is_err());
+    assert!(Mailbox::from_smtp(b"user@\r\n host").is_err());
+    assert!(rcpt_command::<Intl>(b"RCPT TO:<user@host (comment)>\r\n").is_err());
+    assert!(mail_command::<Intl>(b"MAIL FROM:<user@host (comment)>\r\n").is_err());
+
+    assert_eq!(
+        Mailbox::from_imf(b"user@host (comment)").unwrap(),
+        Mailbox(DotAtom("user".into()).into(), dp("host"))
+    );
+    assert_eq!(
+        Mailbox::from_imf(b"user(comment)@host").unwrap(),
+        Mailbox(DotAtom("user".into()).into(), dp("host"))
+    );
+}
+
+#[test]
+fn general_address_literal() {
+    let expected = AddressLiteral::Tagged("x-custom".into(), "data".into());
+    assert_eq!(
+        AddressLiteral::from_smtp(b"[x-custom:data]").unwrap(),
+        expected
+    );
+    assert_eq!(
+        AddressLiteral::from_imf(b"[x-custom:data]").unwrap(),
+        expected
+    );
+    assert_eq!(expected.to_string(), "[x-custom:data]");
+
+    // The tag is an Ldh-str, which cannot end with a hyphen.
+    assert!(AddressLiteral::from_smtp(b"[x-:data]").is_err());
+    assert_eq!(
+        AddressLiteral::from_imf(b"[x-:data]").unwrap(),
+        AddressLiteral::FreeForm("x-:data".into())
+    );
+}
+
+#[test]
+fn vrfy_args() {
+    let mailbox = Mailbox::from_smtp(b"\"a b\"@example.org").unwrap();
+    for input in [&b"\"a b\"@example.org"[..], b"<\"a b\"@example.org>"] {
+        let (rem, parsed) = vrfy_arg::<Intl>(input).unwrap();
+        assert_eq!(rem.len(), 0);
+        assert_eq!(parsed, VrfyArg::Mailbox(mailbox.clone()));
+    }
+
+    let (rem, parsed) = vrfy_arg::<Intl>(b"postmaster").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed, VrfyArg::String(SMTPString("postmaster".into())));
+
+    let (rem, parsed) = vrfy_arg::<Intl>(b"\"Smith, John\"").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed, VrfyArg::String(SMTPString("Smith, John".into())));
+}
+
+#[test]
+fn bdat_chunk_size_bounds() {
+    assert_eq!(
+        bdat_command(b"BDAT 18446744073709551615 LAST\r\n")
+            .unwrap()
+            .1,
+        (u64::MAX, true)
+    );
+    assert!(bdat_command(b"BDAT 18446744073709551616\r\n").is_err());
+    assert!(bdat_command(b"BDAT 99999999999999999999\r\n").is_err());
+}
+
+#[test]
+fn routing_domain() {
+    let mailbox = Mailbox::from_smtp(b"user@example.org").unwrap();
+    assert!(matches!(
+        recipient_domain(&mailbox),
+        Some(Cow::Borrowed("example.org"))
+    ));
+
+    let mailbox = Mailbox::from_smtp(b"user@MAIL.Example.ORG").unwrap();
+    assert_eq!(recipient_domain(&mailbox).unwrap(), "mail.example.org");
+
+    let mailbox = Mailbox::from_smtp("user@ÉCOLE.example".as_bytes()).unwrap();
+    assert_eq!(recipient_domain(&mailbox).unwrap(), "xn--cole-9oa.example");
+
+    let mailbox = Mailbox::from_imf("user@bü_cher.example".as_bytes()).unwrap();
+    assert_eq!(recipient_domain(&mailbox), None);
+
+    let mailbox = Mailbox::from_smtp(b"user@[IPv6:2001:db8::1]").unwrap();
+    assert_eq!(recipient_domain(&mailbox), None);
+}
+
+#[test]
+fn reply_lines() {
+    let (rem, line) = reply_line(b"550 5.1.1 No such user\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        line,
+        ReplyLine {
+            code: 550,
+            more: false,
+            enhanced: Some((5, 1, 1)),
+            text: "No such user".into(),
+        }
+    );
+
+    let (_, line) = reply_line(b"250-mx.example.org greets you\r\n").unwrap();
+    assert!(line.more);
+    assert_eq!(line.enhanced, None);
+    assert_eq!(line.text, "mx.example.org greets you");
+
+    let (_, line) = reply_line(b"221\r\n").unwrap();
+    assert_eq!((line.code, line.text.as_str()), (221, ""));
+
+    // Not an enhanced status code unless followed by a space.
+    let (_, line) = reply_line(b"250 2.0.0.1 queued\r\n").unwrap();
+    assert_eq!(line.enhanced, None);
+    assert!(reply_line(b"250 4.1000.0 x\r\n")
+        .unwrap()
+        .1
+        .enhanced
+        .is_none());
+
+    assert!(reply_line(b"199 too low\r\n").is_err());
+    assert!(reply_line(b"250x\r\n").is_err());
+}
+
+#[test]
+fn mailbox_length_limits() {
+    let local = "a".repeat(64);
+    let label = "b".repeat(63);
+    let domain = format!("{0}.{0}.{0}.{1}", label, "c".repeat(63));
+    assert_eq!(domain.len(), 255);
+
+    let (_, mailbox) =
+        mailbox_with_limits::<Intl>(format!("{}@{}", local, domain).as_bytes()).unwrap();
+    assert_eq!(mailbox.validate(), Ok(()));
+
+    // Quotes count towards the local part.
+    let quoted = format!("\"{}\"@example.org", "a".repeat(63));
+    let mailbox = Mailbox::from_smtp(quoted.as_bytes()).unwrap();
+    assert_eq!(mailbox.validate(), Err("Local part too long"));
+    assert!(mailbox_with_limits::<Intl>(quoted.as_bytes()).is_err());
+
+    let long_domain = format!("{}@{}.example", local, domain);
+    let mailbox = Mailbox::from_smtp(long_domain.as_bytes()).unwrap();
+    assert_eq!(mailbox.validate(), Err("Domain too long"));
+}
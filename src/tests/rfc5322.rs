@@ -1,14 +1,56 @@
+use crate::behaviour::CommentDepth;
+use crate::behaviour::ElideComments;
 use crate::behaviour::Intl;
 use crate::behaviour::Legacy;
+use crate::behaviour::Lenient;
+use crate::behaviour::NoGroupSenders;
+use crate::rfc5322::addr_spec;
+use crate::rfc5322::address_header;
+use crate::rfc5322::any_date;
+use crate::rfc5322::author;
+use crate::rfc5322::bcc;
+use crate::rfc5322::cc;
+use crate::rfc5322::comma_list;
+use crate::rfc5322::comments;
+use crate::rfc5322::date;
+use crate::rfc5322::decoded_headers;
+use crate::rfc5322::display_name_only;
+use crate::rfc5322::extract_addresses;
+use crate::rfc5322::first_addr;
+use crate::rfc5322::flatten_addresses;
+use crate::rfc5322::flatten_addresses_owned;
 use crate::rfc5322::from;
+use crate::rfc5322::from_partial;
+use crate::rfc5322::group;
+use crate::rfc5322::in_reply_to;
+use crate::rfc5322::is_valid_email;
+use crate::rfc5322::is_valid_email_intl;
+use crate::rfc5322::mailbox;
+use crate::rfc5322::mailbox_with_comments;
+use crate::rfc5322::message_id;
+use crate::rfc5322::name_addr_flagged;
+use crate::rfc5322::quote_phrase;
+use crate::rfc5322::received_for;
+use crate::rfc5322::references;
 use crate::rfc5322::reply_to;
 use crate::rfc5322::sender;
+use crate::rfc5322::subject;
+use crate::rfc5322::to;
+use crate::rfc5322::try_mailbox;
 use crate::rfc5322::unstructured;
+use crate::rfc5322::unstructured_full;
+use crate::rfc5322::unstructured_raw;
+use crate::rfc5322::unstructured_strict;
+use crate::rfc5322::unstructured_with_charset;
 use crate::rfc5322::Address;
+use crate::rfc5322::AddressHeaderKind;
+use crate::rfc5322::AddressParseFailure;
 use crate::rfc5322::Group;
 use crate::rfc5322::Mailbox;
+use crate::rfc5322::MailboxParseFailure;
 use crate::types::Mailbox as SMTPMailbox;
 use crate::types::*;
+use std::borrow::Cow;
 
 fn dp<T: Into<String>>(value: T) -> DomainPart {
     DomainPart::Domain(Domain(value.into()))
@@ -190,3 +232,827 @@ fn invalid_latin1() {
     assert_eq!(rem.len(), 0);
     assert_eq!(parsed, "\u{fffd}");
 }
+
+#[test]
+fn normalized_dname() {
+    let parsed = parse_single(from::<Intl>, b"\"  Mary\r\n   Smith \"  <mary@x.test>\r\n");
+    assert_eq!(parsed.dname, Some("  Mary   Smith ".into()));
+    assert_eq!(parsed.normalized_dname(), Some("Mary Smith".into()));
+
+    let parsed = parse_single(from::<Intl>, b"\"  \" <mary@x.test>\r\n");
+    assert_eq!(parsed.normalized_dname(), None);
+
+    let parsed = parse_single(from::<Intl>, b"mary@x.test\r\n");
+    assert_eq!(parsed.normalized_dname(), None);
+}
+
+#[test]
+fn display_name_only_phrase() {
+    let (rem, name) = display_name_only::<Intl>(b" Joe  \"Q.\" Public ").unwrap();
+    assert_eq!(rem, b"");
+    assert_eq!(name, "Joe Q. Public");
+}
+
+#[test]
+fn display_name_literal_then_atom() {
+    let (rem, name) = display_name_only::<Intl>(b"=?utf-8?q?Jos=C3=A9?= Smith").unwrap();
+    assert_eq!(rem, b"");
+    assert_eq!(name, "Jos\u{e9} Smith");
+}
+
+#[test]
+fn comment_between_words() {
+    for input in [
+        &b"John (the) Smith"[..],
+        b"John(the)Smith",
+        b"\"John\"(the)\"Smith\"",
+        b"=?utf-8?q?John?=(the)=?utf-8?q?Smith?=",
+    ] {
+        let (rem, name) = display_name_only::<Intl>(input).unwrap();
+        assert_eq!(rem, b"");
+        assert_eq!(name, "John Smith");
+    }
+}
+
+#[test]
+fn comment_between_words_elided() {
+    type P = ElideComments<Intl>;
+
+    let (_, name) = display_name_only::<P>(b"John(the)Smith").unwrap();
+    assert_eq!(name, "John Smith");
+    let (_, name) = display_name_only::<P>(b"\"John\"(the)\"Smith\"").unwrap();
+    assert_eq!(name, "JohnSmith");
+    let (_, name) = display_name_only::<P>(b"=?utf-8?q?John?= (the) =?utf-8?q?Smith?=").unwrap();
+    assert_eq!(name, "JohnSmith");
+}
+
+#[test]
+fn extract_addresses_malformed() {
+    let input =
+        b"\"Doe, John\" <jdoe@example.org>, broken <<@> x@[127.0.0.1] \"a b\"@example.com junk@";
+    assert_eq!(
+        extract_addresses::<Intl>(input),
+        [
+            SMTPMailbox(DotAtom("jdoe".into()).into(), dp("example.org")),
+            SMTPMailbox(
+                DotAtom("x".into()).into(),
+                DomainPart::Address(AddressLiteral::IP("127.0.0.1".parse().unwrap()))
+            ),
+            SMTPMailbox(QuotedString("a b".into()).into(), dp("example.com")),
+        ]
+    );
+}
+
+#[test]
+fn date_time() {
+    let (rem, parsed) = date::<Intl>(b" 29 Feb 2020\r\n 23:59:60 +0130 (comment)\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.second, 60);
+    assert_eq!(parsed.offset, 5400);
+    assert_eq!(parsed.timestamp(), 1583020800 - 5400);
+
+    assert!(date::<Intl>(b" Mon, 1 Jan 2001 25:00:00 +0000").is_err());
+    assert!(date::<Intl>(b" 29 Feb 2001 00:00:00 +0000").is_err());
+    assert!(date::<Intl>(b" 1 Jan 2001 00:00:00 +0060").is_err());
+}
+
+#[test]
+fn obsolete_date_time() {
+    let input = b" Mon , 01 Jan 99 00:00:00 GMT\r\n";
+    assert_eq!(date::<Intl>(input), date::<Lenient<Intl>>(input));
+    let (rem, parsed) = date::<Intl>(input).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.year, 1999);
+    assert_eq!(parsed.timestamp(), 915148800);
+
+    let (rem, parsed) =
+        date::<Lenient<Intl>>(b"Tue(x), 2 Mar 04 12 : 30 (y) EST (Eastern)").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!((parsed.year, parsed.second), (2004, 0));
+    assert_eq!(parsed.offset, -5 * 3600);
+
+    let (_, parsed) = date::<Lenient<Intl>>(b" 1 Jan 101 00:00:00 Z").unwrap();
+    assert_eq!((parsed.year, parsed.offset), (2001, 0));
+    assert!(date::<Lenient<Intl>>(b" 1 Jan 2001 00:00:00 J").is_err());
+}
+
+#[test]
+fn address_header_kinds() {
+    let (rem, (kind, addrs)) =
+        address_header::<Intl>(b"resent-sender: John <john@example.org>\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(kind, AddressHeaderKind::ResentSender);
+    assert_eq!(addrs.len(), 1);
+
+    let (rem, (kind, addrs)) = address_header::<Intl>(b"Bcc: \r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(kind, AddressHeaderKind::Bcc);
+    assert!(addrs.is_empty());
+
+    assert!(address_header::<Intl>(b"Subject: a@example.org\r\n").is_err());
+    assert!(address_header::<Intl>(b"To: \r\n").is_err());
+}
+
+#[test]
+fn group_display_roundtrip() {
+    let groups = [
+        Group {
+            dname: "undisclosed-recipients".into(),
+            members: vec![],
+        },
+        Group {
+            dname: "Friends, \"Family\"".into(),
+            members: vec![
+                Mailbox {
+                    dname: Some("Jöhn Doe, Jr.".into()),
+                    address: "jdoe@example.org".parse().unwrap(),
+                },
+                Mailbox {
+                    dname: None,
+                    address: "\"a b\"@[127.0.0.1]".parse().unwrap(),
+                },
+                Mailbox {
+                    dname: Some("=?not?encoded?= ".repeat(10)),
+                    address: "x@example.org".parse().unwrap(),
+                },
+            ],
+        },
+    ];
+
+    assert_eq!(groups[0].to_string(), "undisclosed-recipients:;");
+    for group in groups {
+        let displayed = group.to_string();
+        let (rem, parsed) = from::<Intl>(displayed.as_bytes()).unwrap();
+        assert_eq!(rem.len(), 0, "{}", displayed);
+        assert_eq!(parsed, [Address::Group(group)]);
+    }
+}
+
+#[test]
+fn lenient_semicolon_separator() {
+    let input = b"a@x.com; \"B\" <b@y.com>;\r\n";
+    let (rem, _) = from::<Intl>(input).unwrap();
+    assert_ne!(rem.len(), 0);
+
+    let (rem, parsed) = from::<Lenient<Intl>>(input).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        parsed,
+        [
+            Address::Mailbox(Mailbox {
+                dname: None,
+                address: SMTPMailbox(DotAtom("a".into()).into(), dp("x.com")),
+            }),
+            Address::Mailbox(Mailbox {
+                dname: Some("B".into()),
+                address: SMTPMailbox(DotAtom("b".into()).into(), dp("y.com")),
+            }),
+        ]
+    );
+
+    let (rem, parsed) = from::<Lenient<Intl>>(b"a@x.com; g: b@y.com, c@z.com;\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.len(), 2);
+    assert!(matches!(&parsed[1], Address::Group(g) if g.members.len() == 2));
+}
+
+#[test]
+fn received_for_clause() {
+    let (rem, rcpt) = received_for::<Intl>(
+        b" from [2001:db8::1] (helo=x) by mx with SMTP (envelope-from <a@example.org>)\r\n for a@example.net, b@example.net; Mon, 1 Jan 2001 00:00:00 +0000",
+    )
+    .unwrap();
+    assert_eq!(rem, b" Mon, 1 Jan 2001 00:00:00 +0000");
+    assert_eq!(
+        rcpt,
+        Some(SMTPMailbox(DotAtom("a".into()).into(), dp("example.net")))
+    );
+
+    let (_, rcpt) =
+        received_for::<Intl>(b" by mx id <123@mx> via for-loop; Mon, 1 Jan 2001 00:00:00 +0000")
+            .unwrap();
+    assert_eq!(rcpt, None);
+
+    for input in [
+        &b" for <a@b> (x; y); date"[..],
+        b" for <a@b>, (x; y); date",
+        b" for <a@b> ,(x; (y;)); date",
+    ] {
+        let (rem, rcpt) = received_for::<Intl>(input).unwrap();
+        assert_eq!(rem, b" date");
+        assert_eq!(rcpt.unwrap().to_string(), "a@b");
+    }
+}
+
+#[test]
+fn unstructured_raw_value() {
+    let input = b" folded\r\n =?utf-8?b?w6k=?= \r\nnext";
+    let (rem, (decoded, raw)) = unstructured_raw::<Intl>(input).unwrap();
+    assert_eq!(rem, b"\r\nnext");
+    assert_eq!(decoded, " folded é ");
+    assert_eq!(raw, b" folded\r\n =?utf-8?b?w6k=?= ");
+}
+
+#[test]
+fn from_partial_failures() {
+    let input =
+        b"\"Doe, John\" <jdoe@example.org>, @bad, (a, comment) x@y z, team: a@b, c@d;, \r\n";
+    let (addrs, failures) = from_partial::<Intl>(input);
+    assert_eq!(addrs.len(), 2);
+    assert!(matches!(&addrs[1], Address::Group(g) if g.members.len() == 2));
+    assert_eq!(
+        failures,
+        [
+            AddressParseFailure {
+                range: 31..36,
+                reason: "Invalid address"
+            },
+            AddressParseFailure {
+                range: 37..56,
+                reason: "Trailing characters after address"
+            },
+        ]
+    );
+}
+
+#[test]
+fn subject_from_header_section() {
+    let (_, fields) = crate::headersection::header_section(
+        b"From: a@example.org\r\nsubject: =?utf-8?b?Y2Fmw6k=?=\r\n  =?utf-8?q?_au_lait?=\r\n\r\n",
+    )
+    .unwrap();
+    assert_eq!(subject::<Intl>(&fields).unwrap(), "café au lait");
+
+    let (_, fields) = crate::headersection::header_section(b"From: a@example.org\r\n\r\n").unwrap();
+    assert_eq!(subject::<Intl>(&fields), None);
+}
+
+#[test]
+fn comments_folded() {
+    let (rem, parsed) =
+        comments::<Intl>(b" Sent from =?utf-8?q?m=C3=BCnchen?=\r\n by a\r\n\tvery slow robot")
+            .unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed, " Sent from münchen by a\tvery slow robot");
+}
+
+#[test]
+fn unstructured_fast_path() {
+    let inputs: &[&[u8]] = &[
+        b"",
+        b" Re: meeting",
+        b" Re:  meeting\t \r\n",
+        b"\t\r\n",
+        b" a\r\n",
+        b" a\r\nb",
+        b" folded\r\n line",
+        b" =?utf-8?q?x?=",
+        b" a=?b",
+        b" caf\xc3\xa9",
+    ];
+    for input in inputs {
+        assert_eq!(
+            unstructured::<Intl>(input),
+            unstructured_full::<Intl>(input)
+        );
+        assert_eq!(
+            unstructured::<Legacy>(input),
+            unstructured_full::<Legacy>(input)
+        );
+    }
+}
+
+#[test]
+fn quote_phrase_roundtrip() {
+    assert!(matches!(quote_phrase("John Q Public"), Cow::Borrowed(_)));
+    assert_eq!(quote_phrase(""), "\"\"");
+    assert_eq!(quote_phrase("John  Smith"), "\"John  Smith\"");
+
+    let name = r#"O"Brien \ Co."#;
+    let quoted = quote_phrase(name);
+    assert_eq!(quoted, r#""O\"Brien \\ Co.""#);
+    let (rem, parsed) = display_name_only::<Intl>(quoted.as_bytes()).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed, name);
+
+    for name in ["a\r\nBcc: x@y", "a\0b", "Jörg"] {
+        let quoted = quote_phrase(name);
+        assert!(!quoted.contains(['\r', '\n', '\0']), "{:?}", quoted);
+        let (rem, parsed) = display_name_only::<Intl>(quoted.as_bytes()).unwrap();
+        assert_eq!(rem.len(), 0);
+        assert_eq!(parsed, name);
+    }
+}
+
+#[test]
+fn decoded_header_values() {
+    let (_, fields) = crate::headersection::header_section_lenient(
+        b"Subject: Re:\r\n =?iso-8859-1?q?r=E9union?=\r\nX-\xe9: v\r\n\r\n",
+    )
+    .unwrap();
+    let headers: Vec<_> = decoded_headers::<Intl>(&fields).collect();
+    assert!(matches!(headers[0].0, Cow::Borrowed("Subject")));
+    assert_eq!(headers[0].1, "Re: réunion");
+    assert_eq!(headers[1], ("X-\u{fffd}".into(), "v".into()));
+}
+
+#[test]
+fn unstructured_shift_jis() {
+    let input = b" Re: \x89\xef\x8bc\x82\xcc\x8c\x8f =?utf-8?q?caf=C3=A9?=";
+    assert_eq!(
+        unstructured_with_charset::<Intl>(input, encoding_rs::SHIFT_JIS),
+        " Re: 会議の件 café"
+    );
+    assert_eq!(
+        unstructured_with_charset::<Legacy>(input, encoding_rs::SHIFT_JIS),
+        " Re: 会議の件 café"
+    );
+    assert_eq!(
+        unstructured::<Legacy>(input).unwrap().1,
+        " Re: \u{fffd}\u{fffd}\u{fffd}c\u{fffd}\u{fffd}\u{fffd}\u{fffd} café"
+    );
+
+    // Valid UTF-8 is kept with an Intl policy.
+    let input = b" \xe4\xbc\x9a\xe8\xad\xb0 \x89\xef\x8bc";
+    assert_eq!(
+        unstructured_with_charset::<Intl>(input, encoding_rs::SHIFT_JIS),
+        " 会議 会議"
+    );
+}
+
+#[test]
+fn comma_list_empty_elements() {
+    let input = b" , a@example.org ,, (x) , b@example.org ,";
+    let (rem, parsed) = comma_list::<Intl, _, _>(addr_spec::<Intl>, true)(input).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.len(), 2);
+
+    let (rem, parsed) = comma_list::<Intl, _, _>(addr_spec::<Intl>, false)(&input[2..]).unwrap();
+    assert_eq!(rem, b",, (x) , b@example.org ,");
+    assert_eq!(parsed.len(), 1);
+}
+
+#[test]
+fn author_header() {
+    let (rem, parsed) =
+        author::<Intl>(b" \"Jane Doe\" <jane@example.org>, bob@example.org\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.len(), 2);
+
+    let (_, (kind, addrs)) =
+        address_header::<Intl>(b"Author: Jane Doe <jane@example.org>\r\n").unwrap();
+    assert_eq!(kind, AddressHeaderKind::Author);
+    assert_eq!(addrs, parsed[..1]);
+}
+
+#[test]
+fn flagged_display_name() {
+    let (rem, (mailbox, encoded)) =
+        name_addr_flagged::<Intl>(b"Support =?iso-8859-1?q?T=E9am?= <help@example.org>").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(mailbox.dname.as_deref(), Some("Support Téam"));
+    assert!(encoded);
+
+    let (_, (mailbox, encoded)) =
+        name_addr_flagged::<Intl>("\"Support Téam\" <help@example.org>".as_bytes()).unwrap();
+    assert_eq!(mailbox.dname.as_deref(), Some("Support Téam"));
+    assert!(!encoded);
+
+    let (_, (mailbox, encoded)) = name_addr_flagged::<Intl>(b"<help@example.org>").unwrap();
+    assert_eq!(mailbox.dname, None);
+    assert!(!encoded);
+
+    assert!(name_addr_flagged::<Intl>(b"help@example.org").is_err());
+}
+
+#[test]
+fn group_senders() {
+    let group = b"Automated: noreply@example.org;\r\n";
+    let (_, parsed) = sender::<Intl>(group).unwrap();
+    assert!(matches!(parsed, Address::Group(_)));
+    let (_, parsed) = from::<Intl>(group).unwrap();
+    assert!(matches!(parsed[..], [Address::Group(_)]));
+
+    assert!(sender::<NoGroupSenders<Intl>>(group).is_err());
+    assert!(from::<NoGroupSenders<Intl>>(b"a@example.org, Automated:;").is_err());
+
+    let (_, parsed) = from::<NoGroupSenders<Intl>>(b"a@example.org, b@example.org").unwrap();
+    assert_eq!(parsed.len(), 2);
+    let (_, parsed) = sender::<NoGroupSenders<Intl>>(b"a@example.org\r\n").unwrap();
+    assert!(matches!(parsed, Address::Mailbox(_)));
+
+    // Wrappers combine their options.
+    let input = b"a@example.org; b@example.org";
+    assert_eq!(
+        from::<NoGroupSenders<Lenient<Intl>>>(input)
+            .unwrap()
+            .1
+            .len(),
+        2
+    );
+    assert!(from::<NoGroupSenders<Lenient<Intl>>>(b"a@example.org; g:;").is_err());
+}
+
+#[test]
+fn first_address() {
+    let first = |i: &[u8]| first_addr::<Intl>(i).map(|m| m.to_string());
+
+    assert_eq!(
+        first(b" a@example.org, b@example.org"),
+        Some("a@example.org".into())
+    );
+    assert_eq!(
+        first(b" \"Doe, John\" (work) <john@example.org>, broken <<<"),
+        Some("john@example.org".into())
+    );
+    assert_eq!(
+        first(b" J. R. \"Bob\" Dobbs <bob@example.org>"),
+        Some("bob@example.org".into())
+    );
+    assert_eq!(
+        first(b" Team: Alice <alice@example.org>, bob@example.org;"),
+        Some("alice@example.org".into())
+    );
+    assert_eq!(first(b" Undisclosed recipients:;"), None);
+    assert_eq!(first(b" <>"), None);
+}
+
+#[test]
+fn any_date_formats() {
+    let expected = date::<Intl>(b" Tue, 3 Mar 2020 14:05:09 +0100").unwrap().1;
+    for input in [
+        &b" Tue, 3 Mar 2020 14:05:09 +0100\r\n"[..],
+        b" Tue, 3 Mar 2020 14:05:09 +0100 (CET)",
+        b" Tue,  3 Mar 2020 13:05:09 UTC",
+        b" 3 Mar 2020 13:05:09",
+        b" 3 Mar 20 08:05:09 EST",
+        b" Tue Mar  3 13:05:09 2020",
+        b" Tue Mar  3 14:05:09 +0100 2020",
+        b" Tue Mar 3 13:05:09 UTC 2020\r\n",
+    ] {
+        let (rem, parsed) = any_date(input).unwrap();
+        assert!(rem.is_empty(), "{:?}", String::from_utf8_lossy(input));
+        assert_eq!(
+            parsed.timestamp(),
+            expected.timestamp(),
+            "{:?}",
+            String::from_utf8_lossy(input)
+        );
+    }
+
+    assert!(any_date(b" Tue Mar 32 13:05:09 2020").is_err());
+    assert!(any_date(b" yesterday").is_err());
+}
+
+#[test]
+fn destination_headers() {
+    let (rem, parsed) =
+        to::<Intl>(b" Team: alice@example.org, Bob <bob@example.org>;\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        parsed,
+        [Address::Group(Group {
+            dname: "Team".into(),
+            members: vec![
+                Mailbox {
+                    dname: None,
+                    address: SMTPMailbox::from_imf(b"alice@example.org").unwrap(),
+                },
+                Mailbox {
+                    dname: Some("Bob".into()),
+                    address: SMTPMailbox::from_imf(b"bob@example.org").unwrap(),
+                },
+            ],
+        })]
+    );
+
+    let (rem, parsed) = cc::<Intl>(b" a@example.org, \"B\" <b@example.org>\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert!(
+        matches!(&parsed[..], [Address::Mailbox(a), Address::Mailbox(b)]
+        if a.dname.is_none() && b.dname.as_deref() == Some("B"))
+    );
+
+    for empty in [&b""[..], b"\r\n", b" \t\r\n", b" (hidden)\r\n"] {
+        let (rem, parsed) = bcc::<Intl>(empty).unwrap();
+        assert_eq!(rem.len(), 0);
+        assert!(parsed.is_empty());
+    }
+    assert!(to::<Intl>(b" \r\n").is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn date_to_chrono() {
+    use chrono::{DateTime, FixedOffset};
+    use std::convert::TryFrom;
+
+    let (_, parsed) = date::<Intl>(b" 31 Dec 2016 23:59:60 +0100").unwrap();
+    let dt = DateTime::<FixedOffset>::try_from(parsed).unwrap();
+    assert_eq!(dt.timestamp(), parsed.timestamp() - 1);
+    assert_eq!(dt.offset().local_minus_utc(), 3600);
+
+    let (_, parsed) = date::<Intl>(b" 1 Jan 50 00:00:00 PST").unwrap();
+    let dt = DateTime::<FixedOffset>::try_from(parsed).unwrap();
+    assert_eq!(dt.to_rfc3339(), "1950-01-01T00:00:00-08:00");
+
+    for (input, expected) in [
+        (
+            &b" Fri, 21 Nov 1997 09:55:06 GMT\r\n"[..],
+            "1997-11-21T09:55:06+00:00",
+        ),
+        (
+            b" Fri, 21 Nov 1997 09:55:06 UT",
+            "1997-11-21T09:55:06+00:00",
+        ),
+        (
+            b" Fri, 21 Nov 97 09:55:06 -0600\r\n",
+            "1997-11-21T09:55:06-06:00",
+        ),
+        (b" Sat, 1 Mar 49 12:00 EST", "2049-03-01T12:00:00-05:00"),
+    ] {
+        let (rem, parsed) = date::<Intl>(input).unwrap();
+        assert_eq!(rem.len(), 0);
+        let dt = DateTime::<FixedOffset>::try_from(parsed).unwrap();
+        assert_eq!(dt.to_rfc3339(), expected);
+    }
+
+    let (_, parsed) = date::<Intl>(b" 1 Jan 2001 00:00:00 +9900").unwrap();
+    assert!(DateTime::<FixedOffset>::try_from(parsed).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn address_serde_roundtrip() {
+    let (_, parsed) = from::<Intl>(
+        b"\"Doe, Jane\" <jane@example.org>, =?utf-8?q?=C3=89quipe?=: a@example.org, b@example.org;",
+    )
+    .unwrap();
+    let json = serde_json::to_string(&parsed).unwrap();
+    assert_eq!(
+        json,
+        r#"["\"Doe, Jane\" <jane@example.org>","=?utf-8?b?w4lxdWlwZQ==?=: a@example.org, b@example.org;"]"#
+    );
+    assert_eq!(serde_json::from_str::<Vec<Address>>(&json).unwrap(), parsed);
+
+    assert!(serde_json::from_str::<Address>(r#""a@example.org, b@example.org""#).is_err());
+}
+
+#[test]
+fn message_ids() {
+    let (rem, id) =
+        message_id::<Intl>(b" <\r\n (left) abc.def (x) @ (right) example.org>  (trailing)\r\n")
+            .unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(id.to_string(), "abc.def@example.org");
+
+    let (rem, id) = message_id::<Intl>(b"<unique$id@[192.0.2.1]>").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(&*id, "unique$id@[192.0.2.1]");
+
+    assert!(message_id::<Intl>(b" abc@example.org").is_err());
+    assert!(message_id::<Intl>(b" <abc>").is_err());
+    assert!(message_id::<Intl>(b" <abc@[a\r\n b]>").is_err());
+}
+
+#[test]
+fn message_id_lists() {
+    let ids =
+        |v: Vec<crate::rfc5322::MessageId>| v.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+
+    let (rem, parsed) =
+        references::<Intl>(b" <a@example.org> <b@example.org>\r\n <c@example.org>\r\n").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        ids(parsed),
+        ["a@example.org", "b@example.org", "c@example.org"]
+    );
+
+    let input = b" <a@example.org> stray <b@example.org> (Joe's message of Mon) \"x\" y@z";
+    let (rem, _) = in_reply_to::<Intl>(input).unwrap();
+    assert!(!rem.is_empty());
+    let (rem, parsed) = in_reply_to::<Lenient<Intl>>(input).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(ids(parsed), ["a@example.org", "b@example.org"]);
+
+    assert!(references::<Lenient<Intl>>(b" no ids here").is_err());
+}
+
+#[test]
+fn mailbox_comments() {
+    let (rem, (mailbox, comments)) = mailbox_with_comments::<Intl>(
+        b"\"Doe (not a comment)\" (first) <jdoe(in local)@[192.0.2.1] (outer (inner) text)>",
+    )
+    .unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(mailbox.dname.as_deref(), Some("Doe (not a comment)"));
+    assert_eq!(comments, ["first", "in local", "outer (inner) text"]);
+
+    let (_, (_, comments)) =
+        mailbox_with_comments::<Intl>(b"=?utf-8?q?a(b?= <a@example.org>").unwrap();
+    assert!(comments.is_empty());
+
+    // The display name alternative fails after parsing "(x)".
+    let (_, (mailbox, comments)) =
+        mailbox_with_comments::<Intl>(b"a (x) @example.org (y)").unwrap();
+    assert_eq!(mailbox.dname, None);
+    assert_eq!(comments, ["x", "y"]);
+}
+
+#[test]
+fn mailbox_encoded_comments() {
+    let (_, (mailbox, comments)) = mailbox_with_comments::<Intl>(
+        b"joerg@example.org (=?utf-8?q?J=C3=B6rg?= =?utf-8?q?_M=C3=BCller?= (=?utf-8?q?x?=) a=?utf-8?q?y?=)",
+    )
+    .unwrap();
+    assert_eq!(mailbox.address.to_string(), "joerg@example.org");
+    assert_eq!(comments, ["Jörg Müller (x) a=?utf-8?q?y?="]);
+}
+
+#[test]
+fn deeply_nested_comments() {
+    let depth = 50_000;
+    let mut input = b"a@example.org ".to_vec();
+    input.extend(vec![b'('; depth]);
+    input.extend(vec![b')'; depth]);
+
+    let (rem, _) = from::<Intl>(&input).unwrap();
+    assert_eq!(rem.len(), input.len() - 14);
+    assert!(date::<Lenient<Intl>>(&input[13..]).is_err());
+
+    let nested = |n| format!("a@example.org {}{}", "(".repeat(n), ")".repeat(n));
+    assert_eq!(from::<Intl>(nested(100).as_bytes()).unwrap().0, b"");
+    assert_ne!(from::<Intl>(nested(101).as_bytes()).unwrap().0, b"");
+
+    type P = CommentDepth<Intl, 200>;
+    assert_eq!(from::<P>(nested(200).as_bytes()).unwrap().0, b"");
+    assert_ne!(from::<P>(nested(201).as_bytes()).unwrap().0, b"");
+}
+
+#[test]
+fn address_list_display_roundtrip() {
+    let input = b"Undisclosed:;, \"Doe, Jane\" <jane@example.org>, Team: a@example.org, \"Q. Bob\" <b@[192.0.2.1]>;, \"odd local\"@example.org";
+    let (_, parsed) = from::<Intl>(input).unwrap();
+    let rendered = parsed
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    assert_eq!(rendered.as_bytes(), &input[..]);
+    assert_eq!(from::<Intl>(rendered.as_bytes()).unwrap().1, parsed);
+}
+
+#[test]
+fn single_address_parsers() {
+    let (rem, parsed) = mailbox::<Intl>(b"\"Doe, John\" <jdoe@example.org> (work)").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.dname.as_deref(), Some("Doe, John"));
+    assert_eq!(
+        addr_spec::<Intl>(b"jdoe@example.org").unwrap().1,
+        parsed.address
+    );
+
+    // A group is not a mailbox and vice versa.
+    assert!(mailbox::<Intl>(b"Team: jdoe@example.org;").is_err());
+    assert!(group::<Intl>(b"jdoe@example.org").is_err());
+
+    let (rem, team) = group::<Intl>(b"Team: jdoe@example.org; rest").unwrap();
+    assert_eq!(rem, b"rest");
+    assert_eq!(
+        team.members,
+        [Mailbox {
+            dname: None,
+            address: parsed.address
+        }]
+    );
+}
+
+#[test]
+fn flatten_group_and_mailbox() {
+    let (_, addrs) =
+        to::<Intl>(b"Team: a@example.org, Bob <b@example.org>;, c@example.org, Empty:;").unwrap();
+    assert_eq!(addrs.len(), 3);
+
+    let flat = flatten_addresses(&addrs);
+    let names: Vec<_> = flat.iter().map(|m| m.address.to_string()).collect();
+    assert_eq!(names, ["a@example.org", "b@example.org", "c@example.org"]);
+    assert_eq!(flat[1].dname.as_deref(), Some("Bob"));
+
+    let owned = flatten_addresses_owned(addrs.clone());
+    assert_eq!(owned.iter().collect::<Vec<_>>(), flat);
+}
+
+#[test]
+fn obsolete_route() {
+    let input = b"John <@a.example, (relay) @b.example:john@c.example>";
+    assert!(from::<Intl>(input).is_err());
+
+    let (rem, parsed) = from::<Lenient<Intl>>(input).unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        parsed,
+        [Address::Mailbox(Mailbox {
+            dname: Some("John".into()),
+            address: addr_spec::<Intl>(b"john@c.example").unwrap().1,
+        })]
+    );
+
+    let (_, parsed) = from::<Lenient<Intl>>(b"<,@a.example,,:john@c.example>").unwrap();
+    assert_eq!(parsed.len(), 1);
+}
+
+#[test]
+fn address_from_str() {
+    use std::convert::TryFrom;
+
+    let address: Address = "Team: a@example.org;".parse().unwrap();
+    assert!(matches!(&address, Address::Group(g) if g.members.len() == 1));
+    assert_eq!(Address::try_from(&b"Team: a@example.org;"[..]), Ok(address));
+
+    assert!("a@example.org, b@example.org".parse::<Address>().is_err());
+    assert!(Mailbox::try_from(&b"a@example.org>"[..]).is_err());
+    assert!("".parse::<Mailbox>().is_err());
+}
+
+#[test]
+fn partial_mailbox() {
+    let (rem, parsed) = try_mailbox::<Intl>(b"\"Doe, John\" <john at example.org> (x)").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(
+        parsed,
+        Err(MailboxParseFailure {
+            dname: Some("Doe, John".into()),
+            reason: "Invalid address",
+        })
+    );
+
+    let (rem, parsed) = try_mailbox::<Intl>(b"<john@").unwrap();
+    assert_eq!(rem.len(), 0);
+    assert_eq!(parsed.unwrap_err().dname, None);
+
+    assert!(try_mailbox::<Intl>(b"John Doe john@example.org").is_err());
+}
+
+#[test]
+fn valid_email() {
+    for valid in [
+        "a@b",
+        "first.last@example.org",
+        "\"john doe\"@[192.0.2.1]",
+        "a+b@x-y.example",
+    ] {
+        assert!(is_valid_email(valid), "{}", valid);
+        assert!(is_valid_email_intl(valid), "{}", valid);
+    }
+
+    for invalid in [
+        "",
+        "a",
+        "a@",
+        "@b",
+        "a@b@c",
+        "a..b@example.org",
+        "<a@b>",
+        "a@b\r\n",
+        " a@b",
+        "a@b ",
+        "a(x)@b",
+        "a@b (c)",
+        "a @b",
+        "\"a\r\n b\"@c",
+    ] {
+        assert!(!is_valid_email(invalid), "{}", invalid);
+        assert!(!is_valid_email_intl(invalid), "{}", invalid);
+    }
+
+    assert!(!is_valid_email("δοκιμή@παράδειγμα.δοκιμή"));
+    assert!(is_valid_email_intl("δοκιμή@παράδειγμα.δοκιμή"));
+}
+
+#[test]
+fn unterminated_encoded_words() {
+    for input in [
+        &b" =?utf-8?B?abc"[..],
+        b" =?utf-8?q?a b?=",
+        b" x=?utf-8*en?Q?=C3",
+        b" =?iso-8859-1?q?caf=E9?= =?utf-8?b?",
+    ] {
+        assert!(unstructured::<Intl>(input).is_ok());
+        assert!(unstructured_strict::<Intl>(input).is_err(), "{:?}", input);
+    }
+
+    for input in [
+        &b" =? ="[..],
+        b" =?utf-8?",
+        b" =?utf-8?x?abc?=",
+        b" 1+1=?",
+        b" =?utf-8?q?caf=C3=A9?= =?utf-8?b?w6k=?=",
+    ] {
+        assert_eq!(
+            unstructured_strict::<Intl>(input),
+            unstructured::<Intl>(input),
+            "{:?}",
+            input
+        );
+    }
+}
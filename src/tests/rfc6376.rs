@@ -0,0 +1,35 @@
+use crate::headersection::*;
+use crate::rfc6376::*;
+
+// RFC 6376 section 3.4.5
+#[test]
+fn relaxed_header_example() {
+    assert_eq!(canonicalize_relaxed(b"A", b" X"), b"a:X");
+    assert_eq!(canonicalize_relaxed(b"B ", b" Y\t\r\n\tZ  "), b"b:Y Z");
+}
+
+#[test]
+fn relaxed_header_section() {
+    let (_, fields) = header_section(b"Subject:\tToo   many\r\n spaces \t\r\n\r\n").unwrap();
+    let (name, value) = fields[0].unwrap();
+    assert_eq!(
+        canonicalize_relaxed(name, value),
+        b"subject:Too many spaces"
+    );
+}
+
+// RFC 6376 section 3.4.5
+#[test]
+fn body_example() {
+    let body = b" C \r\nD \t E\r\n\r\n\r\n";
+    assert_eq!(canonicalize_body(body, true), b" C\r\nD E\r\n");
+    assert_eq!(canonicalize_body(body, false), b" C \r\nD \t E\r\n");
+}
+
+#[test]
+fn body_edge_cases() {
+    assert_eq!(canonicalize_body(b"", false), b"\r\n");
+    assert_eq!(canonicalize_body(b"\r\n\r\n", true), b"");
+    assert_eq!(canonicalize_body(b"a\r\n\r\nb", false), b"a\r\n\r\nb\r\n");
+    assert_eq!(canonicalize_body(b"a\r\n \t\r\n", true), b"a\r\n");
+}
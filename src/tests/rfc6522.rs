@@ -0,0 +1,21 @@
+use crate::rfc2231::content_type;
+use crate::rfc6522::*;
+
+#[test]
+fn report_types() {
+    let (_, (_, params)) =
+        content_type(b"multipart/report; Report-Type=\"Disposition-Notification\"").unwrap();
+    assert_eq!(
+        report_type(&params),
+        Some(ReportType::DispositionNotification)
+    );
+
+    let (_, (_, params)) = content_type(b"multipart/report; report-type=feedback-report").unwrap();
+    assert_eq!(
+        report_type(&params),
+        Some(ReportType::Other("feedback-report".into()))
+    );
+
+    let (_, (_, params)) = content_type(b"multipart/mixed; boundary=x").unwrap();
+    assert_eq!(report_type(&params), None);
+}
@@ -0,0 +1,35 @@
+use crate::behaviour::Intl;
+use crate::headersection::{emit_header, header_section, unfold, HeaderValue};
+use crate::rfc5322::from;
+use crate::strategy::*;
+use crate::types::Mailbox;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn mailbox_roundtrip(mailbox in mailbox()) {
+        let s = mailbox.to_string();
+        prop_assert_eq!(s.parse::<Mailbox>(), Ok(mailbox.clone()));
+        prop_assert_eq!(Mailbox::from_imf(s.as_bytes()), Ok(mailbox));
+    }
+
+    #[test]
+    fn address_roundtrip(address in address()) {
+        let s = address.to_string();
+        let (rem, parsed) = from::<Intl>(s.as_bytes()).unwrap();
+        prop_assert!(rem.is_empty(), "{:?}", s);
+        prop_assert_eq!(parsed, vec![address]);
+    }
+
+    #[test]
+    fn emit_addresses_roundtrip(addresses in prop::collection::vec(address(), 1..=6)) {
+        let line = emit_header("To", &HeaderValue::Addresses(addresses.clone())).unwrap();
+        prop_assert!(line.lines().all(|l| l.len() <= 998), "{}", line);
+
+        let section = format!("{}\r\n", line);
+        let (_, fields) = header_section(section.as_bytes()).unwrap();
+        let (_, value) = fields[0].unwrap();
+        let (_, parsed) = from::<Intl>(&unfold(value)).unwrap();
+        prop_assert_eq!(parsed, addresses);
+    }
+}
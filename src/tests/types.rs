@@ -0,0 +1,151 @@
+use crate::behaviour::Intl;
+use crate::rfc5322::addr_spec;
+use crate::types::*;
+
+#[cfg(feature = "email-address")]
+#[test]
+fn email_address_roundtrip() {
+    use std::convert::TryFrom;
+
+    let mailbox = Mailbox::from_smtp(b"\"john doe\"@example.org").unwrap();
+    let converted = email_address::EmailAddress::try_from(&mailbox).unwrap();
+    assert_eq!(converted.as_ref(), "\"john doe\"@example.org");
+    assert_eq!(Mailbox::try_from(converted).unwrap(), mailbox);
+
+    let named: email_address::EmailAddress = "John Doe <jdoe@example.org>".parse().unwrap();
+    assert_eq!(
+        Mailbox::try_from(&named).unwrap(),
+        Mailbox::from_smtp(b"jdoe@example.org").unwrap()
+    );
+}
+
+#[test]
+fn subaddress() {
+    let lp = LocalPart::from_smtp(b"first.last-list").unwrap();
+    assert_eq!(lp.subaddress('-'), Some(("first.last", "list")));
+    assert_eq!(lp.subaddress('+'), None);
+
+    let quoted = LocalPart::from_smtp(b"\"user+tag\"").unwrap();
+    assert_eq!(quoted.subaddress('+'), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_roundtrip() {
+    let mailbox = Mailbox::from_smtp(b"\"john \\\"jd\\\" doe\"@[IPv6:2001:db8::1]").unwrap();
+    let json = serde_json::to_string(&mailbox).unwrap();
+    assert_eq!(json, r#""\"john \\\"jd\\\" doe\"@[IPv6:2001:db8::1]""#);
+    assert_eq!(serde_json::from_str::<Mailbox>(&json).unwrap(), mailbox);
+
+    let (lp, dp) = mailbox.into_parts();
+    let json = serde_json::to_string(&(&lp, &dp)).unwrap();
+    assert_eq!(
+        serde_json::from_str::<(LocalPart, DomainPart)>(&json).unwrap(),
+        (lp, dp)
+    );
+
+    let qs: QuotedString = serde_json::from_str(r#""john \"jd\" doe""#).unwrap();
+    assert_eq!(qs.quoted(), r#""john \"jd\" doe""#);
+
+    assert!(serde_json::from_str::<Mailbox>(r#""not an address""#).is_err());
+    assert!(serde_json::from_str::<Domain>(r#""example..org""#).is_err());
+    assert!(serde_json::from_str::<QuotedString>(r#""a\u0000b""#).is_err());
+}
+
+#[test]
+fn address_literal_ip() {
+    let v4 = DomainPart::from_imf(b"[192.0.2.1]").unwrap();
+    assert_eq!(v4.ip(), Some("192.0.2.1".parse().unwrap()));
+
+    for input in [&b"[IPv6:2001:db8::1]"[..], b"[ipv6:2001:DB8::1]"] {
+        let v6 = DomainPart::from_imf(input).unwrap();
+        assert_eq!(v6.ip(), Some("2001:db8::1".parse().unwrap()));
+    }
+
+    // Without the tag an IPv6 address is not recognized.
+    let untagged = AddressLiteral::from_imf(b"[2001:db8::1]").unwrap();
+    assert_eq!(untagged.ip(), None);
+
+    for input in [&b"[999.0.0.1]"[..], b"[192.0.2]", b"[IPv6:2001:db8::g]"] {
+        let lit = AddressLiteral::from_imf(input).unwrap();
+        assert!(matches!(lit, AddressLiteral::FreeForm(_)), "{:?}", lit);
+        assert_eq!(lit.ip(), None);
+    }
+
+    assert_eq!(DomainPart::from_imf(b"example.org").unwrap().ip(), None);
+}
+
+#[test]
+fn mailbox_display_roundtrip() {
+    for input in [
+        &b"first.last@example.org"[..],
+        b"\"john \\\"jd\\\" doe\"@example.org",
+        b"\"a\\\\b\"@[192.0.2.1]",
+        b"\"\"@[IPv6:2001:db8::1]",
+        b"user@[x400:cn=bob]",
+        b"user@[somewhere]",
+        b"j\xc3\xb6rg@b\xc3\xbccher.example",
+    ] {
+        let (_, mailbox) = addr_spec::<Intl>(input).unwrap();
+        let rendered = mailbox.to_string();
+        assert_eq!(rendered.as_bytes(), input);
+        assert_eq!(addr_spec::<Intl>(rendered.as_bytes()).unwrap().1, mailbox);
+    }
+
+    // Needlessly quoted local parts are left quoted.
+    let (_, mailbox) = addr_spec::<Intl>(b"\"john\"@example.org").unwrap();
+    assert_eq!(mailbox.to_string(), "\"john\"@example.org");
+}
+
+#[test]
+fn domain_idna() {
+    let domain = Domain::from_smtp("Bücher.Example".as_bytes()).unwrap();
+    let ascii = domain.to_ascii().unwrap();
+    assert_eq!(&*ascii, "xn--bcher-kva.example");
+    assert_eq!(&*ascii.to_unicode().unwrap(), "bücher.example");
+
+    // ASCII domains are only lowercased.
+    let plain = Domain::from_smtp(b"Mail.Example.ORG").unwrap();
+    assert_eq!(&*plain.to_ascii().unwrap(), "mail.example.org");
+    assert_eq!(&*plain.to_unicode().unwrap(), "mail.example.org");
+
+    let long = Domain::from_imf(format!("{}.example", "a".repeat(64)).as_bytes()).unwrap();
+    assert!(long.to_ascii().is_err());
+}
+
+#[test]
+fn domain_case_insensitive() {
+    use std::collections::HashSet;
+
+    let upper = Domain::from_smtp(b"Example.COM").unwrap();
+    let lower = Domain::from_smtp(b"example.com").unwrap();
+    let set: HashSet<_> = vec![upper.clone(), lower.clone()].into_iter().collect();
+    assert_eq!(set.len(), 1);
+    assert_eq!(upper.cmp(&lower), std::cmp::Ordering::Equal);
+
+    let a = Mailbox::from_smtp(b"user@EXAMPLE.com").unwrap();
+    assert_eq!(a, Mailbox::from_smtp(b"user@example.com").unwrap());
+    assert_ne!(a, Mailbox::from_smtp(b"USER@example.com").unwrap());
+
+    assert!(lower < Domain::from_smtp(b"EXAMPLE.net").unwrap());
+}
+
+#[test]
+fn quote_local_parts() {
+    assert_eq!(quote_local_part("a.b").unwrap(), "a.b");
+    assert_eq!(quote_local_part("a b").unwrap(), r#""a b""#);
+    assert_eq!(quote_local_part(r#"a"b"#).unwrap(), r#""a\"b""#);
+    assert_eq!(quote_local_part(r"a\b").unwrap(), r#""a\\b""#);
+    assert_eq!(quote_local_part("a..b").unwrap(), r#""a..b""#);
+    assert_eq!(quote_local_part("").unwrap(), r#""""#);
+
+    for lp in ["a\r\nb", "a\rb", "a\nb", "a\0b", "a\tb"] {
+        assert_eq!(quote_local_part(lp), None, "{:?}", lp);
+    }
+
+    for lp in ["a.b", "a b", r#"a"b"#, ""] {
+        let quoted = quote_local_part(lp).unwrap();
+        let parsed = LocalPart::from_smtp(quoted.as_bytes()).unwrap();
+        assert_eq!(parsed.to_string(), quoted);
+    }
+}
@@ -17,21 +17,131 @@ use crate::util::*;
 use serde::Deserialize;
 #[cfg(feature = "serde")]
 use serde::Serialize;
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt::Display;
 use std::fmt::{self};
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::net::IpAddr;
 
 /// A domain name such as used by DNS.
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+///
+/// Comparison and hashing ignore ASCII case, so `"Example.ORG"` and
+/// `"example.org"` are equal. Non-ASCII letters are compared as is; use
+/// [`Domain::to_ascii`] first to compare internationalized domains.
+/// Local parts remain case sensitive.
+///
+/// # Examples
+/// ```
+/// use rustyknife::types::Domain;
+///
+/// let a = Domain::from_smtp(b"Example.ORG").unwrap();
+/// assert_eq!(a, Domain::from_smtp(b"example.org").unwrap());
+/// assert_eq!(a.to_string(), "Example.ORG");
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub struct Domain(pub(crate) String);
 string_newtype!(Domain);
+nom_fromstr!(Domain, smtp::domain::<Intl>);
+
+impl PartialEq for Domain {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for Domain {}
+
+impl Hash for Domain {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.folded() {
+            state.write_u8(c);
+        }
+        state.write_u8(0xff);
+    }
+}
+
+impl PartialOrd for Domain {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Domain {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.folded().cmp(other.folded())
+    }
+}
 impl Domain {
     nom_from_smtp!(smtp::domain::<Intl>);
     nom_from_imf!(imf::_domain::<Intl>);
+
+    /// Convert this domain to its ASCII form as per [UTS #46].
+    ///
+    /// Non-ASCII labels are converted to punycode A-labels and the
+    /// result is lowercased. Fails if a label is not a valid host name
+    /// label or if the domain is too long for DNS.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::types::Domain;
+    ///
+    /// let domain = Domain::from_smtp("例え.テスト".as_bytes()).unwrap();
+    /// assert_eq!(domain.to_ascii().unwrap().to_string(), "xn--r8jz45g.xn--zckzah");
+    /// assert!(Domain::from_imf(b"under_score.example").unwrap().to_ascii().is_err());
+    /// ```
+    ///
+    /// [UTS #46]: https://www.unicode.org/reports/tr46/
+    pub fn to_ascii(&self) -> Result<Domain, ()> {
+        idna::uts46::Uts46::new()
+            .to_ascii(
+                self.0.as_bytes(),
+                idna::AsciiDenyList::STD3,
+                idna::uts46::Hyphens::Check,
+                idna::uts46::DnsLength::Verify,
+            )
+            .map(|ascii| Domain(ascii.into_owned()))
+            .map_err(|_| ())
+    }
+
+    /// Convert this domain to its Unicode form as per [UTS #46].
+    ///
+    /// Punycode A-labels are decoded and the result is normalized.
+    /// Fails if a label is not a valid host name label.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::types::Domain;
+    ///
+    /// let domain = Domain::from_smtp(b"XN--R8JZ45G.xn--zckzah").unwrap();
+    /// assert_eq!(domain.to_unicode().unwrap().to_string(), "例え.テスト");
+    /// assert!(Domain::from_imf(b"xn--a.example").unwrap().to_unicode().is_err());
+    /// ```
+    ///
+    /// [UTS #46]: https://www.unicode.org/reports/tr46/
+    pub fn to_unicode(&self) -> Result<Domain, ()> {
+        let (unicode, result) = idna::uts46::Uts46::new().to_unicode(
+            self.0.as_bytes(),
+            idna::AsciiDenyList::STD3,
+            idna::uts46::Hyphens::Check,
+        );
+
+        result.map(|_| Domain(unicode.into_owned())).map_err(|_| ())
+    }
+
+    // The ASCII lowercase bytes, for comparing and hashing.
+    fn folded(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.bytes().map(|c| c.to_ascii_lowercase())
+    }
 }
 
 /// The local part of an address preceding the `"@"` in an email address.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub enum LocalPart {
     /// Simple local part with no spaces.
     DotAtom(DotAtom),
@@ -55,8 +165,34 @@ impl LocalPart {
             LocalPart::DotAtom(_) => (),
         }
     }
+
+    /// The conventional separator for [`subaddress`](Self::subaddress).
+    pub const SUBADDRESS_SEPARATOR: char = '+';
+
+    /// Split off the detail of a subaddressed local part.
+    ///
+    /// Returns the base mailbox and the tag around the first
+    /// `separator`, or `None` if there is none. Quoted local parts are
+    /// never split.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::types::LocalPart;
+    ///
+    /// let lp = LocalPart::from_smtp(b"user+tag+more").unwrap();
+    /// assert_eq!(lp.subaddress(LocalPart::SUBADDRESS_SEPARATOR), Some(("user", "tag+more")));
+    /// assert_eq!(lp.subaddress('-'), None);
+    /// ```
+    pub fn subaddress(&self, separator: char) -> Option<(&str, &str)> {
+        match self {
+            LocalPart::DotAtom(da) => da.0.split_once(separator),
+            LocalPart::Quoted(_) => None,
+        }
+    }
 }
 
+nom_fromstr!(LocalPart, smtp::local_part::<Intl>);
+
 impl From<QuotedString> for LocalPart {
     fn from(value: QuotedString) -> LocalPart {
         LocalPart::Quoted(value)
@@ -74,6 +210,8 @@ impl From<DotAtom> for LocalPart {
 /// This is used in places such as SMTP local parts and IMF display
 /// names.
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub struct QuotedString(pub(crate) String);
 string_newtype!(QuotedString);
 
@@ -106,6 +244,22 @@ impl QuotedString {
     nom_from_imf!(imf::quoted_string::<Intl>);
 }
 
+/// Check the unquoted contents of a quoted string.
+///
+/// Succeeds if quoting `value` gives a valid SMTP quoted string.
+impl std::convert::TryFrom<String> for QuotedString {
+    type Error = &'static str;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let qs = QuotedString(value);
+
+        match smtp::quoted_string::<Intl>(qs.quoted().as_bytes()) {
+            Ok((b"", parsed)) if parsed == qs => Ok(qs),
+            _ => Err("invalid quoted string"),
+        }
+    }
+}
+
 /// A string consisting of atoms separated by periods.
 ///
 /// An atom is a string that may not contain spaces or some special
@@ -115,8 +269,11 @@ impl QuotedString {
 ///
 /// [RFC 5322]: https://tools.ietf.org/html/rfc5322#section-3.2.3
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub struct DotAtom(pub(crate) String);
 string_newtype!(DotAtom);
+nom_fromstr!(DotAtom, smtp::dot_string::<Intl>);
 
 impl DotAtom {
     nom_from_smtp!(smtp::dot_string::<Intl>);
@@ -132,8 +289,46 @@ impl Display for LocalPart {
     }
 }
 
+impl From<LocalPart> for String {
+    fn from(value: LocalPart) -> String {
+        value.to_string()
+    }
+}
+
+/// Quote a local part if needed.
+///
+/// Returns the input unchanged if it is a valid dot-atom. Otherwise it
+/// is returned as a quoted string with `"` and `\\` escaped, as
+/// [`LocalPart`] would display it. Returns [`None`] if the local part
+/// contains characters that can't be quoted, such as control
+/// characters.
+///
+/// Non-ASCII text is allowed unquoted as in [RFC 6531].
+///
+/// # Examples
+/// ```
+/// use rustyknife::types::quote_local_part;
+///
+/// assert_eq!(quote_local_part("john.doe").unwrap(), "john.doe");
+/// assert_eq!(quote_local_part("john doe").unwrap(), "\"john doe\"");
+/// assert_eq!(quote_local_part(".john").unwrap(), "\".john\"");
+/// assert_eq!(quote_local_part("john\r\ndoe"), None);
+/// ```
+///
+/// [RFC 6531]: https://tools.ietf.org/html/rfc6531
+pub fn quote_local_part(local_part: &str) -> Option<Cow<'_, str>> {
+    match smtp::dot_string::<Intl>(local_part.as_bytes()) {
+        Ok((b"", _)) => Some(Cow::Borrowed(local_part)),
+        _ => <QuotedString as std::convert::TryFrom<_>>::try_from(local_part.to_string())
+            .ok()
+            .map(|qs| Cow::Owned(qs.quoted())),
+    }
+}
+
 /// The domain part of an address following the `"@"` in an email address.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub enum DomainPart {
     /// A DNS domain name such as `"example.org"`.
     Domain(Domain),
@@ -142,9 +337,20 @@ pub enum DomainPart {
 }
 
 impl DomainPart {
+    /// Return the IP address if this is an IP address literal.
+    ///
+    /// See [`AddressLiteral::ip`].
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            DomainPart::Address(a) => a.ip(),
+            DomainPart::Domain(_) => None,
+        }
+    }
+
     nom_from_smtp!(smtp::_domain_part::<Intl>);
     nom_from_imf!(imf::domain::<Intl>);
 }
+nom_fromstr!(DomainPart, smtp::_domain_part::<Intl>);
 
 impl From<Domain> for DomainPart {
     fn from(value: Domain) -> DomainPart {
@@ -167,8 +373,19 @@ impl Display for DomainPart {
     }
 }
 
+impl From<DomainPart> for String {
+    fn from(value: DomainPart) -> String {
+        value.to_string()
+    }
+}
+
 /// A network address literal.
+///
+/// With the `serde` feature, [`AddressLiteral::FreeForm`] values can
+/// be serialized but not deserialized since they are not valid SMTP.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub enum AddressLiteral {
     /// An IPv4 or IPv6 address literal.
     /// # Examples
@@ -184,7 +401,11 @@ pub enum AddressLiteral {
     /// assert_eq!(ipv6, AddressLiteral::IP("2001:db8::1".parse().unwrap()));
     /// ```
     IP(IpAddr),
-    /// An address literal in the form tag:value.
+    /// A general address literal in the form tag:value.
+    ///
+    /// This is the `General-address-literal` of RFC 5321, for address
+    /// types other than IPv4 and IPv6. The first field is the
+    /// standardized tag and the second is the content.
     /// # Examples
     /// ```
     /// use std::convert::TryFrom;
@@ -224,9 +445,33 @@ impl AddressLiteral {
         }
     }
 
+    /// Return the IP address of an [`AddressLiteral::IP`] literal.
+    ///
+    /// Free form literals are not upgraded first, but the [crate::rfc5322]
+    /// parsers already upgrade them where possible.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::types::AddressLiteral;
+    ///
+    /// let lit = AddressLiteral::from_imf(b"[192.0.2.1]").unwrap();
+    /// assert_eq!(lit.ip(), Some("192.0.2.1".parse().unwrap()));
+    ///
+    /// let lit = AddressLiteral::from_imf(b"[999.0.0.1]").unwrap();
+    /// assert_eq!(lit, AddressLiteral::FreeForm("999.0.0.1".into()));
+    /// assert_eq!(lit.ip(), None);
+    /// ```
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            AddressLiteral::IP(ip) => Some(*ip),
+            _ => None,
+        }
+    }
+
     nom_from_smtp!(smtp::address_literal);
     nom_from_imf!(imf::domain_literal::<Intl>);
 }
+nom_fromstr!(AddressLiteral, smtp::address_literal);
 
 impl Display for AddressLiteral {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -241,10 +486,16 @@ impl Display for AddressLiteral {
     }
 }
 
+impl From<AddressLiteral> for String {
+    fn from(value: AddressLiteral) -> String {
+        value.to_string()
+    }
+}
+
 /// A valid email address.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "serde", serde(try_from = "&str", into = "String"))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
 pub struct Mailbox(pub(crate) LocalPart, pub(crate) DomainPart);
 
 impl Mailbox {
@@ -275,6 +526,44 @@ impl Mailbox {
         self.0.smtp_try_unquote()
     }
 
+    /// The maximum length of a local part in octets, from [RFC 5321].
+    ///
+    /// [RFC 5321]: https://tools.ietf.org/html/rfc5321#section-4.5.3.1.1
+    pub const MAX_LOCAL_PART_LEN: usize = 64;
+
+    /// The maximum length of a domain in octets, from [RFC 5321].
+    ///
+    /// [RFC 5321]: https://tools.ietf.org/html/rfc5321#section-4.5.3.1.2
+    pub const MAX_DOMAIN_LEN: usize = 255;
+
+    /// Check the SMTP length limits of this address.
+    ///
+    /// The local part including any quotes may be at most
+    /// [`MAX_LOCAL_PART_LEN`](Self::MAX_LOCAL_PART_LEN) octets and the
+    /// domain or address literal at most
+    /// [`MAX_DOMAIN_LEN`](Self::MAX_DOMAIN_LEN) octets. The parsers do
+    /// not enforce these limits; see
+    /// [`rfc5321::mailbox_with_limits`](crate::rfc5321::mailbox_with_limits)
+    /// to reject long addresses while parsing.
+    ///
+    /// # Examples
+    /// ```
+    /// use rustyknife::types::Mailbox;
+    ///
+    /// let long = format!("{}@example.org", "a".repeat(65));
+    /// let mailbox: Mailbox = long.parse().unwrap();
+    /// assert_eq!(mailbox.validate(), Err("Local part too long"));
+    /// ```
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.0.to_string().len() > Self::MAX_LOCAL_PART_LEN {
+            Err("Local part too long")
+        } else if self.1.to_string().len() > Self::MAX_DOMAIN_LEN {
+            Err("Domain too long")
+        } else {
+            Ok(())
+        }
+    }
+
     nom_from_smtp!(smtp::mailbox::<Intl>);
     nom_from_imf!(imf::addr_spec::<Intl>);
 }
@@ -293,3 +582,49 @@ impl From<Mailbox> for String {
         mailbox.to_string()
     }
 }
+
+/// Conversion to the [`email_address`] crate.
+///
+/// Goes through the canonical `"local@domain"` string form, which is
+/// then validated by [`email_address::EmailAddress`].
+#[cfg(feature = "email-address")]
+impl std::convert::TryFrom<&Mailbox> for email_address::EmailAddress {
+    type Error = email_address::Error;
+
+    fn try_from(value: &Mailbox) -> Result<Self, Self::Error> {
+        value.to_string().parse()
+    }
+}
+
+#[cfg(feature = "email-address")]
+impl std::convert::TryFrom<Mailbox> for email_address::EmailAddress {
+    type Error = email_address::Error;
+
+    fn try_from(value: Mailbox) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
+    }
+}
+
+/// Conversion from the [`email_address`] crate.
+///
+/// The address is revalidated using SMTP syntax. This is lossy: an
+/// [`email_address::EmailAddress`] may carry a display name, which is
+/// discarded since a [`Mailbox`] cannot represent it. Comments are
+/// likewise not representable.
+#[cfg(feature = "email-address")]
+impl std::convert::TryFrom<&email_address::EmailAddress> for Mailbox {
+    type Error = ();
+
+    fn try_from(value: &email_address::EmailAddress) -> Result<Self, Self::Error> {
+        Mailbox::from_smtp(value.email().as_bytes()).map_err(|_| ())
+    }
+}
+
+#[cfg(feature = "email-address")]
+impl std::convert::TryFrom<email_address::EmailAddress> for Mailbox {
+    type Error = ();
+
+    fn try_from(value: email_address::EmailAddress) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
+    }
+}
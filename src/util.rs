@@ -1,3 +1,7 @@
+use base64::engine::general_purpose::GeneralPurpose;
+use base64::engine::general_purpose::GeneralPurposeConfig;
+use base64::engine::DecodePaddingMode;
+use base64::Engine as _;
 use nom::bytes::complete::take;
 use nom::combinator::map;
 use nom::combinator::recognize;
@@ -37,6 +41,13 @@ macro_rules! nom_fromstr {
                 nom::exact!(value.as_bytes(), $func).map(|(_, v)| v)
             }
         }
+        impl std::convert::TryFrom<String> for $type {
+            type Error = nom::Err<NomError<'static>>;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                nom::exact!(value.as_bytes(), $func).map(|(_, v)| v)
+            }
+        }
     };
 }
 
@@ -138,3 +149,43 @@ where
 {
     move |input| verify(map(take(1usize), |c: &[u8]| c[0]), |c| pred(*c))(input)
 }
+
+/// Parse a string of ASCII digits as a decimal number.
+///
+/// Returns `None` if `bytes` is empty, contains anything other than
+/// digits or is greater than `max`.
+pub(crate) fn parse_u64_bounded(bytes: &[u8], max: u64) -> Option<u64> {
+    if bytes.is_empty() {
+        return None;
+    }
+
+    bytes
+        .iter()
+        .try_fold(0u64, |acc, &c| {
+            if !c.is_ascii_digit() {
+                return None;
+            }
+            acc.checked_mul(10)?.checked_add(u64::from(c - b'0'))
+        })
+        .filter(|&n| n <= max)
+}
+
+/// Decode base64 from a header value.
+///
+/// Whitespace such as folding is ignored and padding is optional.
+/// Unlike [`crate::rfc2045::decode_base64`], any other character
+/// outside the base64 alphabet is an error.
+pub(crate) fn decode_base64_tolerant(input: &[u8]) -> Option<Vec<u8>> {
+    const ENGINE: GeneralPurpose = GeneralPurpose::new(
+        &base64::alphabet::STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+
+    let filtered: Vec<u8> = input
+        .iter()
+        .copied()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+
+    ENGINE.decode(filtered).ok()
+}